@@ -0,0 +1,94 @@
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Unsigned(u64),
+    Signed(i64),
+    Wide(u128),
+    Text(String),
+    // The solver ran to completion without finding a result
+    Unsolved,
+    // The puzzle has no such part, like day 12 part 2
+    NotApplicable,
+}
+
+impl Answer {
+    pub fn is_solved(&self) -> bool {
+        !matches!(self, Answer::Unsolved | Answer::NotApplicable)
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Unsigned(value) => write!(f, "{value}"),
+            Answer::Signed(value) => write!(f, "{value}"),
+            Answer::Wide(value) => write!(f, "{value}"),
+            Answer::Text(value) => write!(f, "{value}"),
+            Answer::Unsolved => write!(f, "unsolved"),
+            Answer::NotApplicable => write!(f, "n/a"),
+        }
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        Answer::Unsigned(value)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Unsigned(value as u64)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(value: u32) -> Self {
+        Answer::Unsigned(value as u64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::Signed(value)
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        Answer::Wide(value)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::answer::Answer;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(42u64).to_string(), "42");
+        assert_eq!(Answer::from(-7i64).to_string(), "-7");
+        assert_eq!(Answer::from(u128::MAX).to_string(), u128::MAX.to_string());
+        assert_eq!(Answer::from("abc").to_string(), "abc");
+    }
+
+    #[test]
+    fn test_sentinels_are_not_solved() {
+        assert!(Answer::from(0u64).is_solved());
+        assert!(!Answer::Unsolved.is_solved());
+        assert!(!Answer::NotApplicable.is_solved());
+    }
+}
@@ -0,0 +1,129 @@
+use std::{collections::HashMap, fmt::Display, fs, io, path::Path};
+
+use crate::answer::Answer;
+
+// Manifest of accepted answers, one `<day> <part> <file> <answer>` per line
+pub const FILE_NAME: &str = "answers.txt";
+
+#[derive(Debug)]
+pub enum AnswersError {
+    Io(io::Error),
+    Line { line: usize, text: String },
+}
+
+impl Display for AnswersError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnswersError::Io(error) => write!(f, "failed to read answers: {error}"),
+            AnswersError::Line { line, text } => {
+                write!(f, "malformed answer on line {line}: {text}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AnswersError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail { expected: String },
+    Missing,
+    NotApplicable,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail { .. } => write!(f, "fail"),
+            Status::Missing => write!(f, "missing"),
+            Status::NotApplicable => write!(f, "n/a"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Answers {
+    known: HashMap<(u8, u8, String), String>,
+}
+
+impl Answers {
+    pub fn parse(input: &str) -> Result<Self, AnswersError> {
+        let mut known = HashMap::new();
+        for (index, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let malformed = || AnswersError::Line {
+                line: index + 1,
+                text: line.to_string(),
+            };
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let [day, part, file, answer] = parts[..] else {
+                return Err(malformed());
+            };
+            let day = day.parse().map_err(|_| malformed())?;
+            let part = part.parse().map_err(|_| malformed())?;
+            known.insert((day, part, file.to_string()), answer.to_string());
+        }
+        Ok(Self { known })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, AnswersError> {
+        let input = fs::read_to_string(path).map_err(AnswersError::Io)?;
+        Self::parse(&input)
+    }
+
+    pub fn get(&self, day: u8, part: u8, file: &str) -> Option<&str> {
+        self.known
+            .get(&(day, part, file.to_string()))
+            .map(String::as_str)
+    }
+
+    pub fn check(&self, day: u8, part: u8, file: &str, answer: &Answer) -> Status {
+        if *answer == Answer::NotApplicable {
+            return Status::NotApplicable;
+        }
+        match self.get(day, part, file) {
+            None => Status::Missing,
+            Some(expected) if expected == answer.to_string() => Status::Pass,
+            Some(expected) => Status::Fail {
+                expected: expected.to_string(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        answer::Answer,
+        answers::{Answers, AnswersError, Status},
+    };
+
+    #[test]
+    fn test_check_answers() {
+        let answers = Answers::parse("# comment\n\n1 1 1e.txt 3\n1 2 1e.txt 6\n").unwrap();
+        let check = |file, answer: u64| answers.check(1, 1, file, &Answer::from(answer));
+        assert_eq!(check("1e.txt", 3), Status::Pass);
+        assert_eq!(
+            check("1e.txt", 7),
+            Status::Fail {
+                expected: String::from("3")
+            }
+        );
+        assert_eq!(check("1.txt", 3), Status::Missing);
+        assert_eq!(
+            answers.check(1, 2, "1e.txt", &Answer::NotApplicable),
+            Status::NotApplicable
+        );
+    }
+
+    #[test]
+    fn test_malformed_line() {
+        let error = Answers::parse("1 1 1e.txt 3\n1 x 1e.txt 6\n").unwrap_err();
+        assert!(matches!(error, AnswersError::Line { line: 2, .. }));
+    }
+}
@@ -0,0 +1,234 @@
+use std::{
+    fs,
+    hint::black_box,
+    io,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error::ParseError, input::InputKind, registry::Entry};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    // Untimed runs before sampling so caches and the allocator settle
+    pub warmup: usize,
+    pub iterations: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            warmup: 3,
+            iterations: 20,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    pub median_ns: u64,
+    pub p95_ns: u64,
+    pub min_ns: u64,
+    pub max_ns: u64,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &mut [Duration]) -> Self {
+        assert!(!samples.is_empty(), "no samples to summarize");
+        samples.sort_unstable();
+        let nanos = |duration: Duration| duration.as_nanos() as u64;
+        Self {
+            median_ns: nanos(samples[percentile_index(samples.len(), 50)]),
+            p95_ns: nanos(samples[percentile_index(samples.len(), 95)]),
+            min_ns: nanos(samples[0]),
+            max_ns: nanos(samples[samples.len() - 1]),
+        }
+    }
+
+    pub fn median(&self) -> Duration {
+        Duration::from_nanos(self.median_ns)
+    }
+
+    pub fn p95(&self) -> Duration {
+        Duration::from_nanos(self.p95_ns)
+    }
+}
+
+// Nearest rank percentile
+fn percentile_index(count: usize, percentile: usize) -> usize {
+    (count * percentile).div_ceil(100).max(1) - 1
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Measurement {
+    pub day: u8,
+    pub part: u8,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+fn sample<T>(config: &Config, mut run: impl FnMut() -> T) -> Stats {
+    for _ in 0..config.warmup {
+        black_box(run());
+    }
+    let mut samples = Vec::with_capacity(config.iterations);
+    for _ in 0..config.iterations.max(1) {
+        let start = Instant::now();
+        black_box(run());
+        samples.push(start.elapsed());
+    }
+    Stats::from_samples(&mut samples)
+}
+
+pub fn measure(
+    entry: &Entry,
+    input: &str,
+    kind: InputKind,
+    config: &Config,
+) -> Result<Measurement, ParseError> {
+    let parsed = entry.parse(input, kind)?;
+    let parse = sample(config, || entry.parse(input, kind));
+    let solve = sample(config, || entry.solve_parsed(&parsed, kind));
+    Ok(Measurement {
+        day: entry.day,
+        part: entry.part,
+        parse,
+        solve,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Parse,
+    Solve,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub day: u8,
+    pub part: u8,
+    pub phase: Phase,
+    pub baseline: Duration,
+    pub current: Duration,
+}
+
+impl Regression {
+    pub fn slowdown(&self) -> f64 {
+        self.current.as_secs_f64() / self.baseline.as_secs_f64()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub measurements: Vec<Measurement>,
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        serde_json::from_str(&text).map_err(io::Error::other)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let text = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, text + "\n")
+    }
+
+    // Medians slower than the baseline by more than `threshold`, 0.1 allows 10% slack
+    pub fn regressions(&self, current: &Measurement, threshold: f64) -> Vec<Regression> {
+        let Some(baseline) = self
+            .measurements
+            .iter()
+            .find(|entry| entry.day == current.day && entry.part == current.part)
+        else {
+            return vec![];
+        };
+
+        let phases = [
+            (Phase::Parse, baseline.parse, current.parse),
+            (Phase::Solve, baseline.solve, current.solve),
+        ];
+        phases
+            .into_iter()
+            .filter(|(_, before, after)| {
+                after.median_ns as f64 > before.median_ns as f64 * (1.0 + threshold)
+            })
+            .map(|(phase, before, after)| Regression {
+                day: current.day,
+                part: current.part,
+                phase,
+                baseline: before.median(),
+                current: after.median(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::bench::{Baseline, Measurement, Phase, Stats};
+
+    fn stats(median_ns: u64) -> Stats {
+        Stats {
+            median_ns,
+            p95_ns: median_ns,
+            min_ns: median_ns,
+            max_ns: median_ns,
+        }
+    }
+
+    #[test]
+    fn test_stats_from_samples() {
+        let mut samples: Vec<Duration> = (1..=20).rev().map(Duration::from_nanos).collect();
+        let stats = Stats::from_samples(&mut samples);
+        assert_eq!(stats.median_ns, 10);
+        assert_eq!(stats.p95_ns, 19);
+        assert_eq!((stats.min_ns, stats.max_ns), (1, 20));
+
+        let stats = Stats::from_samples(&mut [Duration::from_nanos(7)]);
+        assert_eq!((stats.median_ns, stats.p95_ns), (7, 7));
+    }
+
+    #[test]
+    fn test_regressions() {
+        let baseline = Baseline {
+            measurements: vec![Measurement {
+                day: 9,
+                part: 2,
+                parse: stats(100),
+                solve: stats(1000),
+            }],
+        };
+        let current = Measurement {
+            day: 9,
+            part: 2,
+            parse: stats(105),
+            solve: stats(1500),
+        };
+        let regressions = baseline.regressions(&current, 0.1);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].phase, Phase::Solve);
+        assert_eq!(regressions[0].slowdown(), 1.5);
+
+        let unknown = Measurement { day: 1, ..current };
+        assert!(baseline.regressions(&unknown, 0.1).is_empty());
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let baseline = Baseline {
+            measurements: vec![Measurement {
+                day: 1,
+                part: 2,
+                parse: stats(3),
+                solve: stats(4),
+            }],
+        };
+        let text = serde_json::to_string(&baseline).unwrap();
+        assert_eq!(serde_json::from_str::<Baseline>(&text).unwrap(), baseline);
+    }
+}
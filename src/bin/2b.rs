@@ -1,3 +0,0 @@
-fn main() {
-    println!("{}", aoc2025::d2::part_2(aoc2025::input::D2));
-}
@@ -1,3 +0,0 @@
-fn main() {
-    println!("{}", aoc2025::d6::part_1(aoc2025::input::D6));
-}
@@ -0,0 +1,80 @@
+use std::time::{Duration, Instant};
+
+use aoc2025::{answers::Status, input::InputKind, registry, report::Record, runner};
+
+use crate::{CliError, Options, bench::format_duration};
+
+// Every registered part, days run concurrently on the rayon pool
+pub fn all(options: &Options) -> Result<(), CliError> {
+    if !options.positional.is_empty() {
+        return Err("all takes no day or part".into());
+    }
+    let kind = options.kind.unwrap_or(InputKind::Real);
+    let answers = options.load_answers()?;
+    let entries: Vec<_> = registry::ENTRIES.iter().collect();
+
+    let start = Instant::now();
+    let outcomes = runner::run_all(&entries, &options.source, kind);
+    let wall = start.elapsed();
+
+    let mut failed = 0;
+    if !options.json {
+        println!(
+            "{:>3} {:>4}  {:>20} {:>10}  status",
+            "day", "part", "answer", "time"
+        );
+    }
+    for outcome in &outcomes {
+        let (answer, status) = match &outcome.result {
+            Ok(answer) => {
+                let status = answers.check(outcome.day, outcome.part, &outcome.file, answer);
+                if let Status::Fail { .. } = status {
+                    failed += 1;
+                }
+                (answer.to_string(), status.to_string())
+            }
+            Err(_) => {
+                failed += 1;
+                (String::from("-"), String::from("error"))
+            }
+        };
+        if options.json {
+            let record = Record {
+                status: Some(status),
+                ..Record::from_outcome(outcome, &options.source)
+            };
+            println!("{}", record.to_json());
+            continue;
+        }
+        let status = match &outcome.result {
+            // Parse errors span several lines, the first one is enough for the table
+            Err(error) => {
+                let message = error.to_string();
+                format!("error: {}", message.lines().next().unwrap_or_default())
+            }
+            Ok(_) => status,
+        };
+        println!(
+            "{:>3} {:>4}  {:>20} {:>10}  {status}",
+            outcome.day,
+            outcome.part,
+            answer,
+            format_duration(outcome.duration)
+        );
+    }
+
+    if !options.json {
+        let total: Duration = outcomes.iter().map(|outcome| outcome.duration).sum();
+        println!(
+            "\ntotal {} across {} parts, {} wall",
+            format_duration(total),
+            outcomes.len(),
+            format_duration(wall)
+        );
+    }
+
+    if failed > 0 {
+        return Err(CliError::Failed(format!("{failed} parts failed")));
+    }
+    Ok(())
+}
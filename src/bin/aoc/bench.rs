@@ -0,0 +1,157 @@
+use std::{path::PathBuf, time::Duration};
+
+use aoc2025::{
+    bench::{self, Baseline, Config, Measurement, Phase},
+    error::Error,
+    input::InputKind,
+    registry::Entry,
+    report::{self, Record},
+    runner::Outcome,
+};
+
+use crate::{CliError, Options};
+
+pub struct BenchOptions {
+    pub config: Config,
+    pub save: Option<PathBuf>,
+    pub baseline: Option<PathBuf>,
+    pub threshold: f64,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            config: Config::default(),
+            save: None,
+            baseline: None,
+            threshold: 0.1,
+        }
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    match nanos {
+        0..1_000 => format!("{nanos}ns"),
+        1_000..1_000_000 => format!("{:.1}µs", nanos as f64 / 1e3),
+        1_000_000..1_000_000_000 => format!("{:.1}ms", nanos as f64 / 1e6),
+        _ => format!("{:.2}s", nanos as f64 / 1e9),
+    }
+}
+
+// Answer from one extra untimed run, duration is the sum of the medians
+fn record(
+    entry: &Entry,
+    input: &str,
+    kind: InputKind,
+    options: &Options,
+    measurement: &Measurement,
+) -> Record {
+    let outcome = Outcome {
+        day: entry.day,
+        part: entry.part,
+        kind,
+        file: options.source.name(entry.day, entry.part, kind),
+        input_hash: Some(report::input_hash(input)),
+        result: entry.solve(input, kind).map_err(Error::from),
+        duration: measurement.parse.median() + measurement.solve.median(),
+    };
+    Record {
+        bench: Some(measurement.clone()),
+        ..Record::from_outcome(&outcome, &options.source)
+    }
+}
+
+pub fn bench(options: &Options) -> Result<(), CliError> {
+    let kind = options.kind.unwrap_or(InputKind::Real);
+    let config = &options.bench.config;
+    let baseline = match &options.bench.baseline {
+        Some(path) => Some(Baseline::load(path).map_err(|error| {
+            CliError::Failed(format!(
+                "failed to load baseline={}: {error}",
+                path.display()
+            ))
+        })?),
+        None => None,
+    };
+
+    let mut measurements = vec![];
+    let mut regressions = vec![];
+
+    if !options.json {
+        println!(
+            "{:>3} {:>4}  {:>10} {:>10}  {:>10} {:>10}",
+            "day", "part", "parse p50", "p95", "solve p50", "p95"
+        );
+    }
+    for entry in options.entries()? {
+        let input = entry
+            .load(&options.source, kind)
+            .map_err(|error| CliError::Failed(error.to_string()))?;
+        let measurement = bench::measure(entry, &input, kind, config)
+            .map_err(|error| CliError::Failed(error.to_string()))?;
+
+        if options.json {
+            println!(
+                "{}",
+                record(entry, &input, kind, options, &measurement).to_json()
+            );
+        } else {
+            println!(
+                "{:>3} {:>4}  {:>10} {:>10}  {:>10} {:>10}",
+                entry.day,
+                entry.part,
+                format_duration(measurement.parse.median()),
+                format_duration(measurement.parse.p95()),
+                format_duration(measurement.solve.median()),
+                format_duration(measurement.solve.p95()),
+            );
+        }
+
+        if let Some(baseline) = &baseline {
+            regressions.extend(baseline.regressions(&measurement, options.bench.threshold));
+        }
+        measurements.push(measurement);
+    }
+
+    if let Some(path) = &options.bench.save {
+        Baseline { measurements }.save(path).map_err(|error| {
+            CliError::Failed(format!(
+                "failed to save baseline={}: {error}",
+                path.display()
+            ))
+        })?;
+    }
+
+    if regressions.is_empty() {
+        return Ok(());
+    }
+
+    if !options.json {
+        println!();
+    }
+    for regression in &regressions {
+        let phase = match regression.phase {
+            Phase::Parse => "parse",
+            Phase::Solve => "solve",
+        };
+        let line = format!(
+            "regression: day {} part {} {phase} {} -> {} ({:.2}x)",
+            regression.day,
+            regression.part,
+            format_duration(regression.baseline),
+            format_duration(regression.current),
+            regression.slowdown(),
+        );
+        // Keep stdout valid JSON lines
+        if options.json {
+            eprintln!("{line}");
+        } else {
+            println!("{line}");
+        }
+    }
+    Err(CliError::Failed(format!(
+        "{} regressions against the baseline",
+        regressions.len()
+    )))
+}
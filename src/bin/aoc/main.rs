@@ -0,0 +1,353 @@
+mod all;
+mod bench;
+mod verify;
+
+use std::{
+    io::{self, BufRead},
+    path::PathBuf,
+    process::ExitCode,
+    str::FromStr,
+};
+
+use aoc2025::{
+    answers::{self, Answers},
+    d1::{self, Dial, Instruction, Sweep},
+    generate,
+    input::{DEFAULT_DIR, InputKind, Source},
+    registry::{self, Entry},
+    report::Record,
+    runner,
+};
+
+const USAGE: &str = "\
+usage: aoc <command> [args]
+
+commands:
+  run <day> [part] [options]      solve one part, or both parts of a day
+  all [options]                   solve every part concurrently with a summary table
+  verify [day] [part] [options]   compare answers against the answers manifest
+  bench [day] [part] [options]    time parsing and solving separately
+  gen <day> [options]             print a random input for stress testing
+  trace 1 [options]               print every day 1 rotation as CSV
+  sweep 1 [options]               day 1 counts for every start position of the dial
+  list                            list every registered day and part
+
+options:
+  -e, --example          use the example input instead of the real one
+  -r, --real             use the real input (default for run, verify checks both)
+  -i, --input <file>     read the input from a file, `-` reads stdin
+  -d, --input-dir <dir>  directory with `N.txt`/`Ne.txt` files
+                         (default: $AOC_INPUT_DIR or ./input)
+  -a, --answers <file>   answers manifest (default: answers.txt in the input dir)
+  --json                 print one JSON object per part instead of tables
+
+gen options:
+  --seed <n>             seed for the generator, same seed gives the same input (default: 0)
+  --size <n>             lines or items to generate, grids are n wide (default: 1000)
+
+trace and sweep options:
+  --dial-size <n>        clicks on the dial (default: 100)
+  --start <n>            position the dial starts at (default: 50)
+
+bench options:
+  -w, --warmup <n>       untimed runs before sampling (default: 3)
+  -n, --iterations <n>   timed runs per phase (default: 20)
+  --save <file>          write the measurements as a JSON baseline
+  --baseline <file>      flag medians slower than a saved baseline
+  --threshold <percent>  allowed slowdown before flagging (default: 10)";
+
+pub enum CliError {
+    // Bad arguments, reported together with the usage text
+    Usage(String),
+    Failed(String),
+}
+
+impl From<String> for CliError {
+    fn from(value: String) -> Self {
+        CliError::Usage(value)
+    }
+}
+
+impl From<&str> for CliError {
+    fn from(value: &str) -> Self {
+        CliError::Usage(value.to_string())
+    }
+}
+
+pub struct Options {
+    // None lets each command pick its own default
+    pub kind: Option<InputKind>,
+    pub source: Source,
+    pub answers: Option<PathBuf>,
+    pub json: bool,
+    pub bench: bench::BenchOptions,
+    pub seed: u64,
+    pub size: usize,
+    pub dial: Dial,
+    pub positional: Vec<String>,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, CliError> {
+        let mut options = Options {
+            kind: None,
+            source: Source::from_env(),
+            answers: None,
+            json: false,
+            bench: bench::BenchOptions::default(),
+            seed: 0,
+            size: 1000,
+            dial: Dial::default(),
+            positional: vec![],
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--example" | "-e" => options.kind = Some(InputKind::Example),
+                "--real" | "-r" => options.kind = Some(InputKind::Real),
+                "--input" | "-i" => {
+                    let value = args.next().ok_or("--input expects a file")?;
+                    options.source = Source::from_arg(value);
+                }
+                "--input-dir" | "-d" => {
+                    let value = args.next().ok_or("--input-dir expects a directory")?;
+                    options.source = Source::Dir(PathBuf::from(value));
+                }
+                "--answers" | "-a" => {
+                    let value = args.next().ok_or("--answers expects a file")?;
+                    options.answers = Some(PathBuf::from(value));
+                }
+                "--json" => options.json = true,
+                "--warmup" | "-w" => options.bench.config.warmup = parse_number(args.next())?,
+                "--iterations" | "-n" => {
+                    options.bench.config.iterations = parse_number(args.next())?
+                }
+                "--save" => {
+                    let value = args.next().ok_or("--save expects a file")?;
+                    options.bench.save = Some(PathBuf::from(value));
+                }
+                "--baseline" => {
+                    let value = args.next().ok_or("--baseline expects a file")?;
+                    options.bench.baseline = Some(PathBuf::from(value));
+                }
+                "--seed" => options.seed = parse_number(args.next())?,
+                "--size" => options.size = parse_number(args.next())?,
+                "--dial-size" => options.dial.size = parse_number(args.next())?,
+                "--start" => options.dial.start = parse_number(args.next())?,
+                "--threshold" => {
+                    let percent: usize = parse_number(args.next())?;
+                    options.bench.threshold = percent as f64 / 100.0;
+                }
+                flag if flag.starts_with('-') => {
+                    return Err(format!("unknown flag={flag}").into());
+                }
+                _ => options.positional.push(arg.clone()),
+            }
+        }
+        Ok(options)
+    }
+
+    // Empty day and part select everything
+    pub fn entries(&self) -> Result<Vec<&'static Entry>, CliError> {
+        let entries = match &self.positional[..] {
+            [] => registry::ENTRIES.iter().collect(),
+            [day] => registry::day(parse_day(day)?).collect(),
+            [day, part] => {
+                let day = parse_day(day)?;
+                let part = parse_part(part)?;
+                registry::find(day, part).into_iter().collect()
+            }
+            _ => return Err("expected [day] [part]".into()),
+        };
+        Ok(entries)
+    }
+
+    // Without a manifest next to the inputs every answer is reported as missing
+    pub fn load_answers(&self) -> Result<Answers, CliError> {
+        let path = match (&self.answers, &self.source) {
+            (Some(path), _) => path.clone(),
+            (None, Source::Dir(dir)) => dir.join(answers::FILE_NAME),
+            (None, _) => PathBuf::from(DEFAULT_DIR).join(answers::FILE_NAME),
+        };
+        if self.answers.is_none() && !path.exists() {
+            return Ok(Answers::default());
+        }
+        Answers::load(&path).map_err(|error| CliError::Failed(error.to_string()))
+    }
+}
+
+fn parse_number<T: FromStr>(value: Option<&String>) -> Result<T, CliError> {
+    let value = value.ok_or("expected a number")?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid number={value}").into())
+}
+
+fn parse_day(value: &str) -> Result<u8, String> {
+    match value.parse::<u8>() {
+        Ok(day @ 1..=12) => Ok(day),
+        _ => Err(format!("invalid day={value}, expected 1-12")),
+    }
+}
+
+fn parse_part(value: &str) -> Result<u8, String> {
+    match value {
+        "1" | "a" => Ok(1),
+        "2" | "b" => Ok(2),
+        _ => Err(format!("invalid part={value}, expected 1 or 2")),
+    }
+}
+
+fn run(options: &Options) -> Result<(), CliError> {
+    if options.positional.is_empty() {
+        return Err("run expects <day> [part]".into());
+    }
+    let kind = options.kind.unwrap_or(InputKind::Real);
+
+    let entries = options.entries()?;
+    for outcome in runner::run_day(&entries, &options.source, kind) {
+        if options.json {
+            println!(
+                "{}",
+                Record::from_outcome(&outcome, &options.source).to_json()
+            );
+        }
+        let answer = outcome
+            .result
+            .map_err(|error| CliError::Failed(error.to_string()))?;
+        if !options.json {
+            println!("{answer}");
+        }
+    }
+
+    Ok(())
+}
+
+fn generate(options: &Options) -> Result<(), CliError> {
+    let [day] = &options.positional[..] else {
+        return Err("gen expects <day>".into());
+    };
+    let day = parse_day(day)?;
+    let Some(input) = generate::generate(day, options.seed, options.size) else {
+        let days: Vec<String> = generate::DAYS.iter().map(u8::to_string).collect();
+        return Err(CliError::Failed(format!(
+            "no generator for day={day}, available: {}",
+            days.join(" ")
+        )));
+    };
+    print!("{input}");
+    Ok(())
+}
+
+fn d1_reader(options: &Options, command: &str) -> Result<Box<dyn BufRead>, CliError> {
+    if options.positional != ["1"] {
+        return Err(format!("{command} is only available for day 1").into());
+    }
+    if options.dial.size == 0 {
+        return Err("invalid dial size=0".into());
+    }
+    let kind = options.kind.unwrap_or(InputKind::Real);
+    options
+        .source
+        .reader(1, 1, kind)
+        .map_err(|error| CliError::Failed(error.to_string()))
+}
+
+fn d1_instructions(options: &Options, command: &str) -> Result<Vec<Instruction>, CliError> {
+    d1::stream(d1_reader(options, command)?)
+        .collect::<Result<_, _>>()
+        .map_err(|error| CliError::Failed(error.to_string()))
+}
+
+// Streams the log so traces of inputs larger than memory work
+fn trace(options: &Options) -> Result<(), CliError> {
+    let reader = d1_reader(options, "trace")?;
+    // Sweep tries every start, only the trace follows one
+    let Dial { size, start } = options.dial;
+    if start >= size {
+        return Err(format!("invalid dial start={start} for size={size}").into());
+    }
+    let mut failure = None;
+    let instructions =
+        d1::stream(reader).map_while(|result| result.map_err(|error| failure = Some(error)).ok());
+    d1::write_trace(options.dial.steps(instructions), io::stdout().lock())
+        .map_err(|error| CliError::Failed(error.to_string()))?;
+    match failure {
+        Some(error) => Err(CliError::Failed(error.to_string())),
+        None => Ok(()),
+    }
+}
+
+fn sweep(options: &Options) -> Result<(), CliError> {
+    let instructions = d1_instructions(options, "sweep")?;
+    let sweep = d1::sweep(options.dial.size, &instructions);
+
+    println!("{:>5} {:>8} {:>8}", "start", "part 1", "part 2");
+    for (start, (part_1, part_2)) in sweep.part_1.iter().zip(&sweep.part_2).enumerate() {
+        println!("{start:>5} {part_1:>8} {part_2:>8}");
+    }
+
+    let format_starts = |starts: &Vec<u64>| {
+        let starts: Vec<String> = starts.iter().map(u64::to_string).collect();
+        starts.join(" ")
+    };
+    for (name, counts) in [("part 1", &sweep.part_1), ("part 2", &sweep.part_2)] {
+        let distribution = Sweep::distribution(counts);
+        let (worst, worst_starts) = distribution.first_key_value().unwrap();
+        let (best, best_starts) = distribution.last_key_value().unwrap();
+        // `count x starts` for every count that occurs
+        let spread: Vec<String> = distribution
+            .iter()
+            .map(|(count, starts)| format!("{count}x{}", starts.len()))
+            .collect();
+        println!("\n{name}: {}", spread.join(" "));
+        println!("  best {best} from {}", format_starts(best_starts));
+        println!("  worst {worst} from {}", format_starts(worst_starts));
+    }
+    Ok(())
+}
+
+fn list() {
+    for entry in &registry::ENTRIES {
+        println!("{} {}", entry.day, entry.part);
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((command, rest)) = args.split_first() else {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2);
+    };
+
+    let result = match command.as_str() {
+        "run" => Options::parse(rest).and_then(|options| run(&options)),
+        "all" => Options::parse(rest).and_then(|options| all::all(&options)),
+        "verify" => Options::parse(rest).and_then(|options| verify::verify(&options)),
+        "bench" => Options::parse(rest).and_then(|options| bench::bench(&options)),
+        "gen" => Options::parse(rest).and_then(|options| generate(&options)),
+        "trace" => Options::parse(rest).and_then(|options| trace(&options)),
+        "sweep" => Options::parse(rest).and_then(|options| sweep(&options)),
+        "list" => {
+            list();
+            Ok(())
+        }
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => Err(format!("unknown command={command}").into()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(CliError::Usage(message)) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+        Err(CliError::Failed(message)) => {
+            eprintln!("error: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use aoc2025::{answers::Status, input::InputKind, report::Record, runner};
+
+use crate::{CliError, Options};
+
+pub fn verify(options: &Options) -> Result<(), CliError> {
+    let answers = options.load_answers()?;
+    let entries = options.entries()?;
+    let kinds = match options.kind {
+        Some(kind) => vec![kind],
+        None => vec![InputKind::Real, InputKind::Example],
+    };
+
+    let (mut pass, mut fail, mut missing, mut errors) = (0, 0, 0, 0);
+
+    if !options.json {
+        println!(
+            "{:>3} {:>4}  {:<10} {:<8} answer",
+            "day", "part", "file", "status"
+        );
+    }
+    for kind in kinds {
+        for outcome in runner::run_all(&entries, &options.source, kind) {
+            let (status, detail) = match &outcome.result {
+                Ok(answer) => {
+                    let status = answers.check(outcome.day, outcome.part, &outcome.file, answer);
+                    let detail = match &status {
+                        Status::Pass => {
+                            pass += 1;
+                            answer.to_string()
+                        }
+                        Status::Fail { expected } => {
+                            fail += 1;
+                            format!("{answer} (expected {expected})")
+                        }
+                        Status::Missing => {
+                            missing += 1;
+                            answer.to_string()
+                        }
+                        Status::NotApplicable => String::new(),
+                    };
+                    (status.to_string(), detail)
+                }
+                Err(error) => {
+                    errors += 1;
+                    (String::from("error"), error.to_string())
+                }
+            };
+            if options.json {
+                let record = Record {
+                    status: Some(status),
+                    ..Record::from_outcome(&outcome, &options.source)
+                };
+                println!("{}", record.to_json());
+                continue;
+            }
+            println!(
+                "{:>3} {:>4}  {:<10} {:<8} {detail}",
+                outcome.day, outcome.part, outcome.file, status
+            );
+        }
+    }
+
+    if !options.json {
+        println!("\n{pass} pass, {fail} fail, {missing} missing, {errors} error");
+    }
+
+    if fail > 0 || errors > 0 {
+        return Err(CliError::Failed(String::from("verification failed")));
+    }
+    Ok(())
+}
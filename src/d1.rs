@@ -1,80 +1,622 @@
-fn parse_instruction(input_str: &str) -> Option<(bool, u32)> {
-    if input_str.is_empty() {
-        return None;
+use std::{
+    collections::BTreeMap,
+    io::{self, BufRead, Write},
+    ops::Range,
+};
+
+use crate::{
+    answer::Answer,
+    error::{Error, ParseError},
+    input::InputError,
+    solution::Solution,
+};
+
+const DAY: u8 = 1;
+
+// Direction, true turns right towards higher numbers, and distance in clicks
+pub type Instruction = (bool, u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dial {
+    pub size: u64,
+    pub start: u64,
+}
+
+impl Default for Dial {
+    fn default() -> Self {
+        Self::new(100, 50)
     }
+}
 
-    let (direction_string, distance_string) = input_str.split_at(1);
+impl Dial {
+    pub const fn new(size: u64, start: u64) -> Self {
+        assert!(start < size, "dial start has to be below its size");
+        Self { size, start }
+    }
 
-    let dir = match direction_string {
-        "L" => false,
-        "R" => true,
-        _ => return None,
-    };
+    pub fn steps<I: IntoIterator<Item = Instruction>>(
+        &self,
+        instructions: I,
+    ) -> Steps<I::IntoIter> {
+        Steps {
+            dial: *self,
+            position: self.start,
+            instructions: instructions.into_iter(),
+        }
+    }
 
-    let Ok(distance) = distance_string.parse::<u32>() else {
-        return None;
-    };
+    // Where the dial ends up and how many clicks along the way point at zero, the last included
+    // A rotation by zero that rests on zero still counts as landing there
+    pub fn rotate(&self, position: u64, (right, distance): Instruction) -> (u64, u64) {
+        if distance == 0 {
+            return (position, (position == 0) as u64);
+        }
+        if right {
+            // Split off the full turns so distances close to u64::MAX do not overflow
+            let end = position + distance % self.size;
+            return (end % self.size, distance / self.size + end / self.size);
+        }
+        let end = (position + self.size - distance % self.size) % self.size;
+        let hits = match position {
+            0 => distance / self.size,
+            _ if distance >= position => (distance - position) / self.size + 1,
+            _ => 0,
+        };
+        (end, hits)
+    }
+}
 
-    Some((dir, distance))
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub instruction: Instruction,
+    pub position: u64,
+    // The rotation stopped at zero
+    pub landed: bool,
+    // Clicks that went past zero, the landing not included
+    pub passed: u64,
 }
 
-pub fn part_1(input_str: &str) -> String {
-    let mut dial: i32 = 50;
-    let mut counter = 0;
+impl Step {
+    pub fn hits(&self) -> u64 {
+        self.passed + self.landed as u64
+    }
+}
 
-    for line in input_str.lines() {
-        if line.is_empty() {
-            continue;
+pub struct Steps<I> {
+    dial: Dial,
+    position: u64,
+    instructions: I,
+}
+
+impl<I: Iterator<Item = Instruction>> Iterator for Steps<I> {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let instruction = self.instructions.next()?;
+        let (position, hits) = self.dial.rotate(self.position, instruction);
+        self.position = position;
+        let landed = position == 0;
+        Some(Step {
+            instruction,
+            position,
+            landed,
+            passed: hits - landed as u64,
+        })
+    }
+}
+
+pub fn write_trace(steps: impl Iterator<Item = Step>, mut writer: impl Write) -> io::Result<()> {
+    writeln!(writer, "index,instruction,position,landed,passed")?;
+    for (index, step) in steps.enumerate() {
+        let (right, distance) = step.instruction;
+        let direction = if right { 'R' } else { 'L' };
+        writeln!(
+            writer,
+            "{index},{direction}{distance},{},{},{}",
+            step.position, step.landed as u8, step.passed
+        )?;
+    }
+    Ok(())
+}
+
+// Effect of a run of instructions for every start position at once. Rotations only shift
+// the dial, so the end is always `start + offset`, while the zero hits depend on the start
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Span {
+    offset: u64,
+    hits: Vec<u64>,
+}
+
+impl Span {
+    fn identity(size: u64) -> Self {
+        Self {
+            offset: 0,
+            hits: vec![0; size as usize],
         }
-        let Some((direction, distance)) = parse_instruction(line) else {
-            panic!("failed to parse line={line}");
+    }
+
+    fn of(dial: &Dial, instruction: Instruction) -> Self {
+        Self {
+            offset: dial.rotate(0, instruction).0,
+            hits: (0..dial.size)
+                .map(|start| dial.rotate(start, instruction).1)
+                .collect(),
+        }
+    }
+
+    fn then(&self, next: &Span) -> Span {
+        let size = self.hits.len() as u64;
+        Span {
+            offset: (self.offset + next.offset) % size,
+            hits: (0..size)
+                .map(|start| self.hits[start as usize] + next.hits[self.end(start) as usize])
+                .collect(),
+        }
+    }
+
+    fn end(&self, start: u64) -> u64 {
+        (start + self.offset) % self.hits.len() as u64
+    }
+}
+
+// Segment tree of spans, queries walk O(log n) nodes and updates rebuild O(log n) spans
+pub struct RotationTree {
+    dial: Dial,
+    len: usize,
+    leaves: usize,
+    nodes: Vec<Span>,
+}
+
+impl RotationTree {
+    pub fn new(size: u64, instructions: &[Instruction]) -> Self {
+        let dial = Dial::new(size, 0);
+        let leaves = instructions.len().next_power_of_two();
+        let mut nodes = vec![Span::identity(size); 2 * leaves];
+        for (index, instruction) in instructions.iter().enumerate() {
+            nodes[leaves + index] = Span::of(&dial, *instruction);
+        }
+        for node in (1..leaves).rev() {
+            nodes[node] = nodes[2 * node].then(&nodes[2 * node + 1]);
+        }
+        Self {
+            dial,
+            len: instructions.len(),
+            leaves,
+            nodes,
+        }
+    }
+
+    pub fn update(&mut self, index: usize, instruction: Instruction) {
+        assert!(index < self.len, "instruction index out of range");
+        let mut node = self.leaves + index;
+        self.nodes[node] = Span::of(&self.dial, instruction);
+        while node > 1 {
+            node /= 2;
+            self.nodes[node] = self.nodes[2 * node].then(&self.nodes[2 * node + 1]);
+        }
+    }
+
+    // End position and zero hits of running `range` from `start`, like `Dial::rotate`
+    pub fn query(&self, range: Range<usize>, start: u64) -> (u64, u64) {
+        assert!(
+            start < self.dial.size,
+            "start has to be below the dial size"
+        );
+        assert!(range.end <= self.len, "instruction range out of bounds");
+
+        let mut left = vec![];
+        let mut right = vec![];
+        let (mut low, mut high) = (range.start + self.leaves, range.end + self.leaves);
+        while low < high {
+            if low % 2 == 1 {
+                left.push(low);
+                low += 1;
+            }
+            if high % 2 == 1 {
+                high -= 1;
+                right.push(high);
+            }
+            low /= 2;
+            high /= 2;
+        }
+
+        let mut position = start;
+        let mut hits = 0;
+        for node in left.into_iter().chain(right.into_iter().rev()) {
+            let span = &self.nodes[node];
+            hits += span.hits[position as usize];
+            position = span.end(position);
+        }
+        (position, hits)
+    }
+}
+
+// Part 1 and part 2 counts for every start position, indexed by the start
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sweep {
+    pub part_1: Vec<u64>,
+    pub part_2: Vec<u64>,
+}
+
+impl Sweep {
+    // Start positions grouped by the count they give, lowest count first
+    pub fn distribution(counts: &[u64]) -> BTreeMap<u64, Vec<u64>> {
+        let mut distribution: BTreeMap<u64, Vec<u64>> = BTreeMap::new();
+        for (start, count) in counts.iter().enumerate() {
+            distribution.entry(*count).or_default().push(start as u64);
+        }
+        distribution
+    }
+}
+
+// One pass over the instructions for all starts at once. The dial sits at `start + offset`,
+// so a landing pins down a single start, and the extra zero a partial turn may pick up
+// covers an interval of starts which is added to a difference array
+pub fn sweep(size: u64, instructions: &[Instruction]) -> Sweep {
+    let mut landings = vec![0; size as usize];
+    let mut extra = vec![0i64; size as usize + 1];
+    let mut full_turns = 0;
+    let mut offset = 0;
+
+    for &(right, distance) in instructions {
+        let rest = distance % size;
+        full_turns += distance / size;
+
+        // Positions before the rotation that pass or land on zero once more
+        let positions = match (distance, rest, right) {
+            (0, _, _) => Some((0, 0)),
+            (_, 0, _) => None,
+            (_, _, true) => Some((size - rest, size - 1)),
+            (_, _, false) => Some((1, rest)),
         };
-        match direction {
-            false => dial -= distance as i32,
-            true => dial += distance as i32,
+        if let Some((first, last)) = positions {
+            let start = (first + size - offset) % size;
+            let end = start + last - first + 1;
+            extra[start as usize] += 1;
+            if end <= size {
+                extra[end as usize] -= 1;
+            } else {
+                extra[size as usize] -= 1;
+                extra[0] += 1;
+                extra[(end - size) as usize] -= 1;
+            }
         }
-        dial %= 100;
 
-        if dial == 0 {
-            counter += 1
+        offset = match right {
+            true => (offset + rest) % size,
+            false => (offset + size - rest) % size,
+        };
+        landings[((size - offset) % size) as usize] += 1;
+    }
+
+    let mut running = 0;
+    let part_2 = extra[..size as usize]
+        .iter()
+        .map(|change| {
+            running += change;
+            full_turns + running as u64
+        })
+        .collect();
+    Sweep {
+        part_1: landings,
+        part_2,
+    }
+}
+
+// `L`/`R` or the signed `-`/`+` followed by a distance
+fn parse_instruction(index: usize, input_str: &str) -> Result<Instruction, ParseError> {
+    let Some((direction_string, distance_string)) = input_str.split_at_checked(1) else {
+        return Err(ParseError::new(
+            DAY,
+            index,
+            0,
+            input_str,
+            "expected L, R, - or +",
+        ));
+    };
+
+    let dir = match direction_string {
+        "L" | "-" => false,
+        "R" | "+" => true,
+        _ => {
+            return Err(ParseError::new(
+                DAY,
+                index,
+                0,
+                input_str,
+                "expected L, R, - or +",
+            ));
         }
+    };
+
+    let Ok(distance) = distance_string.parse::<u64>() else {
+        return Err(ParseError::new(
+            DAY,
+            index,
+            1,
+            input_str,
+            "invalid distance",
+        ));
+    };
+
+    Ok((dir, distance))
+}
+
+// Parses one line at a time from `reader` reusing a single buffer, so the log can be
+// far larger than memory. Reading stops after an I/O error
+pub struct Stream<R> {
+    reader: R,
+    line: String,
+    index: usize,
+    failed: bool,
+}
+
+pub fn stream<R: BufRead>(reader: R) -> Stream<R> {
+    Stream {
+        reader,
+        line: String::new(),
+        index: 0,
+        failed: false,
     }
+}
 
-    counter.to_string()
+impl<R: BufRead> Iterator for Stream<R> {
+    type Item = Result<Instruction, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.failed {
+            self.line.clear();
+            let index = self.index;
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(_) => self.index += 1,
+                Err(error) => {
+                    self.failed = true;
+                    return Some(Err(InputError::Read(error).into()));
+                }
+            }
+            let line = self.line.trim_end_matches(['\n', '\r']);
+            if !line.is_empty() {
+                return Some(parse_instruction(index, line).map_err(Error::from));
+            }
+        }
+        None
+    }
 }
 
-pub fn part_2(input_str: &str) -> String {
-    let mut dial: i32 = 50;
-    let mut counter = 0;
+// Part 1 and part 2 counts without holding the instructions in memory
+pub fn count_stream<R: BufRead>(dial: &Dial, reader: R) -> Result<(u64, u64), Error> {
+    let mut position = dial.start;
+    let (mut landings, mut hits) = (0, 0);
+    for instruction in stream(reader) {
+        let (end, step_hits) = dial.rotate(position, instruction?);
+        position = end;
+        landings += (end == 0) as u64;
+        hits += step_hits;
+    }
+    Ok((landings, hits))
+}
 
-    for line in input_str.lines() {
-        let prev = dial;
+pub fn try_parse(input_str: &str) -> Result<Vec<Instruction>, ParseError> {
+    let mut instructions = vec![];
+    for (index, line) in input_str.lines().enumerate() {
         if line.is_empty() {
             continue;
         }
-        let Some((direction, distance)) = parse_instruction(line) else {
-            panic!("failed to parse line={line}");
-        };
-        let mod_distance = distance % 100;
-        let rem_distance = distance - mod_distance;
-        let distance_revolutions = rem_distance / 100;
-        match direction {
-            false => dial -= mod_distance as i32,
-            true => dial += mod_distance as i32,
-        }
-        let unconstrained = dial;
+        instructions.push(parse_instruction(index, line)?);
+    }
+    Ok(instructions)
+}
 
-        dial %= 100;
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Parsed = Vec<Instruction>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        try_parse(input)
+    }
+
+    fn part_1(&self, instructions: &Self::Parsed) -> Answer {
+        Dial::default()
+            .steps(instructions.iter().copied())
+            .filter(|step| step.landed)
+            .count()
+            .into()
+    }
+
+    fn part_2(&self, instructions: &Self::Parsed) -> Answer {
+        Dial::default()
+            .steps(instructions.iter().copied())
+            .map(|step| step.hits())
+            .sum::<u64>()
+            .into()
+    }
+}
 
-        if dial < 0 {
-            dial += 100;
+pub fn part_1(input_str: &str) -> Result<Answer, ParseError> {
+    Ok(Day1.part_1(&try_parse(input_str)?))
+}
+
+pub fn part_2(input_str: &str) -> Result<Answer, ParseError> {
+    Ok(Day1.part_2(&try_parse(input_str)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::{
+        d1::{
+            Dial, Instruction, RotationTree, Sweep, count_stream, stream, sweep, try_parse,
+            write_trace,
+        },
+        error::Error,
+        input::D1E,
+    };
+
+    fn replay(size: u64, instructions: &[Instruction], start: u64) -> (u64, u64) {
+        Dial::new(size, start)
+            .steps(instructions.iter().copied())
+            .fold((start, 0), |(_, hits), step| {
+                (step.position, hits + step.hits())
+            })
+    }
+
+    #[test]
+    fn test_example_trace() {
+        let instructions = try_parse(D1E).unwrap();
+        let steps: Vec<(u64, bool, u64)> = Dial::default()
+            .steps(instructions)
+            .map(|step| (step.position, step.landed, step.passed))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                (82, false, 1),
+                (52, false, 0),
+                (0, true, 0),
+                (95, false, 0),
+                (55, false, 1),
+                (0, true, 0),
+                (99, false, 0),
+                (0, true, 0),
+                (14, false, 0),
+                (32, false, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rotate_full_turns() {
+        let dial = Dial::new(10, 0);
+        assert_eq!(dial.rotate(0, (true, 25)), (5, 2));
+        assert_eq!(dial.rotate(0, (false, 25)), (5, 2));
+        assert_eq!(dial.rotate(3, (false, 3)), (0, 1));
+        assert_eq!(dial.rotate(3, (false, 13)), (0, 2));
+        assert_eq!(dial.rotate(3, (false, 2)), (1, 0));
+        assert_eq!(dial.rotate(0, (true, 0)), (0, 1));
+    }
+
+    #[test]
+    fn test_csv_trace() {
+        let mut output = vec![];
+        write_trace(Dial::new(10, 5).steps([(false, 7), (true, 2)]), &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "index,instruction,position,landed,passed\n0,L7,8,0,1\n1,R2,0,1,0\n"
+        );
+    }
+
+    #[test]
+    fn test_malformed_line() {
+        let error = try_parse("L68\nL30\nX48\n").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 1));
+
+        let error = try_parse("L68\nR4x\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        assert_eq!(error.text, "R4x");
+    }
+
+    #[test]
+    fn test_rotation_tree_example() {
+        let instructions = try_parse(D1E).unwrap();
+        let mut tree = RotationTree::new(100, &instructions);
+        assert_eq!(tree.query(0..10, 50), (32, 6));
+        assert_eq!(tree.query(2..5, 52), (55, 2));
+        assert_eq!(tree.query(3..3, 7), (7, 0));
+
+        tree.update(0, (true, 50));
+        assert_eq!(tree.query(0..1, 50), (0, 1));
+    }
+
+    proptest! {
+        #[test]
+        fn test_rotation_tree_matches_replay(
+            size in 1u64..20,
+            mut instructions in prop::collection::vec((any::<bool>(), 0u64..50), 1..40),
+            updates in prop::collection::vec((any::<prop::sample::Index>(), any::<bool>(), 0u64..50), 0..5),
+            range in (any::<prop::sample::Index>(), any::<prop::sample::Index>()),
+            start in any::<prop::sample::Index>(),
+        ) {
+            let mut tree = RotationTree::new(size, &instructions);
+            for (index, right, distance) in updates {
+                let index = index.index(instructions.len());
+                instructions[index] = (right, distance);
+                tree.update(index, (right, distance));
+            }
+            let (a, b) = (range.0.index(instructions.len() + 1), range.1.index(instructions.len() + 1));
+            let range = a.min(b)..a.max(b);
+            let start = start.index(size as usize) as u64;
+            prop_assert_eq!(
+                tree.query(range.clone(), start),
+                replay(size, &instructions[range], start)
+            );
         }
+    }
 
-        if dial == 0 || (prev != 0 && dial != unconstrained) {
-            counter += 1;
+    fn replay_sweep(size: u64, instructions: &[Instruction]) -> Sweep {
+        let (part_1, part_2) = (0..size)
+            .map(|start| {
+                let steps = Dial::new(size, start).steps(instructions.iter().copied());
+                steps.fold((0, 0), |(landings, hits), step| {
+                    (landings + step.landed as u64, hits + step.hits())
+                })
+            })
+            .unzip();
+        Sweep { part_1, part_2 }
+    }
+
+    #[test]
+    fn test_sweep_example() {
+        let instructions = try_parse(D1E).unwrap();
+        let sweep = sweep(100, &instructions);
+        assert_eq!((sweep.part_1[50], sweep.part_2[50]), (3, 6));
+        assert_eq!(sweep, replay_sweep(100, &instructions));
+
+        let distribution = Sweep::distribution(&sweep.part_1);
+        assert_eq!(distribution.last_key_value().unwrap(), (&3, &vec![50]));
+    }
+
+    proptest! {
+        #[test]
+        fn test_sweep_matches_replay(
+            size in 1u64..20,
+            instructions in prop::collection::vec((any::<bool>(), 0u64..50), 0..30),
+        ) {
+            prop_assert_eq!(sweep(size, &instructions), replay_sweep(size, &instructions));
         }
-        counter += distance_revolutions;
     }
 
-    counter.to_string()
+    #[test]
+    fn test_stream_matches_parse() {
+        let streamed: Vec<Instruction> = stream(D1E.as_bytes()).map(Result::unwrap).collect();
+        assert_eq!(streamed, try_parse(D1E).unwrap());
+        assert_eq!(
+            count_stream(&Dial::default(), D1E.as_bytes()).unwrap(),
+            (3, 6)
+        );
+    }
+
+    #[test]
+    fn test_stream_signed_and_wide() {
+        let input = "+18446744073709551615\r\n\n-5\nR0\n";
+        let streamed: Vec<Instruction> = stream(input.as_bytes()).map(Result::unwrap).collect();
+        assert_eq!(streamed, vec![(true, u64::MAX), (false, 5), (true, 0)]);
+
+        let (end, hits) = Dial::default().rotate(50, (true, u64::MAX));
+        assert_eq!((end, hits), (65, u64::MAX / 100));
+    }
+
+    #[test]
+    fn test_stream_malformed_line() {
+        let results: Vec<Result<Instruction, Error>> =
+            stream("L1\n\nX2\n+3\n".as_bytes()).collect();
+        assert_eq!(results.len(), 3);
+        let Err(Error::Parse(error)) = &results[1] else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.line, error.column), (3, 1));
+        assert_eq!(results[2].as_ref().unwrap(), &(true, 3));
+    }
 }
@@ -5,6 +5,10 @@
 
 use std::collections::HashSet;
 
+use crate::{answer::Answer, error::ParseError, solution::Solution};
+
+const DAY: u8 = 10;
+
 const BUTTON_MASK: [u64; 10] = [
     0b0000000001,
     0b0000000010,
@@ -18,77 +22,180 @@ const BUTTON_MASK: [u64; 10] = [
     0b1000000000,
 ];
 
-struct Machine {
+#[derive(Debug)]
+pub struct Machine {
     needed_lights: u64,
     buttons: Vec<u64>,
     #[allow(dead_code)]
     joltage_requirements: Vec<u64>,
 }
 
-fn light_diagram_from_str(string: &str) -> u64 {
+fn light_diagram_from_str(index: usize, line: &str, string: &str) -> Result<u64, ParseError> {
+    let Some(inner) = string
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+    else {
+        return Err(ParseError::at_token(
+            DAY,
+            index,
+            line,
+            string,
+            "expected [lights]",
+        ));
+    };
+
     let mut diagram = 0;
-    for char in string.chars().rev() {
+    for (light, (offset, char)) in inner.char_indices().enumerate() {
+        let token = &inner[offset..offset + char.len_utf8()];
+        if light >= BUTTON_MASK.len() {
+            return Err(ParseError::at_token(
+                DAY,
+                index,
+                line,
+                token,
+                "at most 10 lights",
+            ));
+        }
         match char {
-            '.' => diagram <<= 1,
-            '#' => diagram = (diagram << 1) | 1,
-            _ => continue,
+            '.' => {}
+            '#' => diagram |= BUTTON_MASK[light],
+            _ => {
+                return Err(ParseError::at_token(
+                    DAY,
+                    index,
+                    line,
+                    token,
+                    "expected . or #",
+                ));
+            }
         }
     }
-    diagram
+    Ok(diagram)
 }
 
-fn button_from_str(string: &str) -> u64 {
+fn button_from_str(
+    index: usize,
+    line: &str,
+    string: &str,
+    lights: usize,
+) -> Result<u64, ParseError> {
+    let Some(inner) = string
+        .strip_prefix('(')
+        .and_then(|rest| rest.strip_suffix(')'))
+    else {
+        return Err(ParseError::at_token(
+            DAY,
+            index,
+            line,
+            string,
+            "expected (lights)",
+        ));
+    };
+
     let mut button = 0;
-    for char in string.chars() {
-        match char {
-            '0' => button |= BUTTON_MASK[0],
-            '1' => button |= BUTTON_MASK[1],
-            '2' => button |= BUTTON_MASK[2],
-            '3' => button |= BUTTON_MASK[3],
-            '4' => button |= BUTTON_MASK[4],
-            '5' => button |= BUTTON_MASK[5],
-            '6' => button |= BUTTON_MASK[6],
-            '7' => button |= BUTTON_MASK[7],
-            '8' => button |= BUTTON_MASK[8],
-            '9' => button |= BUTTON_MASK[9],
-            _ => continue,
-        }
+    for part in inner.split(',') {
+        let Some(mask) = part
+            .parse::<usize>()
+            .ok()
+            .filter(|&light| light < lights)
+            .and_then(|light| BUTTON_MASK.get(light))
+        else {
+            return Err(ParseError::at_token(
+                DAY,
+                index,
+                line,
+                part,
+                format!("expected a light below {lights}"),
+            ));
+        };
+        button |= mask;
     }
-    button
+    Ok(button)
 }
 
-fn joltage_from_str(string: &str) -> Vec<u64> {
-    let end = string.len() - 1;
+fn joltage_from_str(
+    index: usize,
+    line: &str,
+    string: &str,
+    lights: usize,
+) -> Result<Vec<u64>, ParseError> {
     // Remove prefix and suffix brackets
-    let inner = &string[1..end];
+    let Some(inner) = string
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+    else {
+        return Err(ParseError::at_token(
+            DAY,
+            index,
+            line,
+            string,
+            "expected {joltages}",
+        ));
+    };
 
-    inner.split(',').map(|part| part.parse().unwrap()).collect()
+    let mut joltages = vec![];
+    for part in inner.split(',') {
+        let Ok(joltage) = part.parse() else {
+            return Err(ParseError::at_token(
+                DAY,
+                index,
+                line,
+                part,
+                "invalid joltage",
+            ));
+        };
+        joltages.push(joltage);
+    }
+    if joltages.len() != lights {
+        return Err(ParseError::at_token(
+            DAY,
+            index,
+            line,
+            string,
+            format!("expected {lights} joltages, found {}", joltages.len()),
+        ));
+    }
+    Ok(joltages)
 }
 
-impl From<&str> for Machine {
-    fn from(value: &str) -> Self {
-        let parts: Vec<&str> = value.split_whitespace().collect();
-        if parts.len() < 3 {
-            panic!("need 3 parts or more to describe a machine");
-        }
-        let len = parts.len();
-        let light_diagram_str = parts[0];
-        let joltage_requirements_str = parts[len - 1];
-        let buttons_str = &parts[1..(len - 1)];
-
-        let needed = light_diagram_from_str(light_diagram_str);
-        let mut buttons = vec![];
-        for button_str in buttons_str {
-            buttons.push(button_from_str(button_str));
-        }
-        let joltage_requirements = joltage_from_str(joltage_requirements_str);
-
-        Self {
-            needed_lights: needed,
-            buttons,
-            joltage_requirements,
-        }
+fn parse_machine(index: usize, line: &str) -> Result<Machine, ParseError> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() < 3 {
+        return Err(ParseError::new(
+            DAY,
+            index,
+            line.len(),
+            line,
+            "need 3 parts or more to describe a machine",
+        ));
+    }
+    let len = parts.len();
+    let light_diagram_str = parts[0];
+    let joltage_requirements_str = parts[len - 1];
+    let buttons_str = &parts[1..(len - 1)];
+
+    let needed = light_diagram_from_str(index, line, light_diagram_str)?;
+    // The diagram only holds `.` and `#` between its brackets once it parses
+    let lights = light_diagram_str.len() - 2;
+    let mut buttons = vec![];
+    for button_str in buttons_str {
+        buttons.push(button_from_str(index, line, button_str, lights)?);
     }
+    let joltage_requirements = joltage_from_str(index, line, joltage_requirements_str, lights)?;
+
+    Ok(Machine {
+        needed_lights: needed,
+        buttons,
+        joltage_requirements,
+    })
+}
+
+pub fn try_parse(input: &str) -> Result<Vec<Machine>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| parse_machine(index, line))
+        .collect()
 }
 
 fn light_alternatives(current: u64, buttons: &[u64]) -> Vec<u64> {
@@ -106,24 +213,26 @@ fn button_joltages(button: u64) -> Vec<usize> {
 }
 
 impl Machine {
-    fn solve_lights(&self, max_depth: usize) -> usize {
+    // None when no presses light the pattern, or not within `max_depth` presses
+    fn solve_lights(&self, max_depth: usize) -> Option<usize> {
+        if self.needed_lights == 0 {
+            return Some(0);
+        }
         let mut iteration = 0;
         // Record of seen states to avoid loops
         let mut seen_states: HashSet<u64> = HashSet::new();
         seen_states.insert(0);
         let mut search_space = vec![0];
 
-        loop {
-            if iteration >= max_depth {
-                panic!("failed to solve machine within {max_depth} iterations");
-            }
-
+        // Every reachable state is seen once, so the search space runs dry when the pattern
+        // is not one of them
+        while iteration < max_depth && !search_space.is_empty() {
             let mut next_state = vec![];
 
             for light in &search_space {
                 for alternative in light_alternatives(*light, &self.buttons) {
                     if alternative == self.needed_lights {
-                        return iteration + 1;
+                        return Some(iteration + 1);
                     }
                     if !seen_states.contains(&alternative) {
                         seen_states.insert(alternative);
@@ -136,6 +245,7 @@ impl Machine {
 
             iteration += 1;
         }
+        None
     }
 }
 
@@ -289,6 +399,18 @@ impl Matrix {
         Some(solution)
     }
 
+    // A row without variables left that still asks for a joltage, only after `row_echelon_form`
+    fn inconsistent(&self) -> bool {
+        let variable_count = self.width - 1;
+        (0..self.height()).any(|row| {
+            let values = &self.values[row * self.width..(row + 1) * self.width];
+            values[..variable_count]
+                .iter()
+                .all(|value| value.abs() < 1e-9)
+                && values[variable_count].abs() > 1e-9
+        })
+    }
+
     fn augmented_column(&self) -> Vec<f64> {
         (0..self.height())
             .map(|row| self.values[row * self.width + self.width - 1])
@@ -373,16 +495,23 @@ fn joltage_depth_first_search(
     }
 }
 
-fn joltage_solve(machine: &Machine) -> (Vec<f64>, usize) {
+// None when no whole, non negative number of presses meets the requirements
+fn joltage_solve(machine: &Machine) -> Option<(Vec<f64>, usize)> {
     let mut matrix = joltage_matrix(machine);
     let maximum_value: usize = matrix.augmented_column().iter().sum::<f64>().round() as usize;
     matrix.reduced_row_echelon_form();
+    if matrix.inconsistent() {
+        return None;
+    }
     let variable_count = matrix.free_indices().len();
 
     if variable_count == 0 {
-        let solution = matrix.augmented_column();
+        let solution = matrix.solve(&[])?;
+        if !valid_joltage_solution(&solution) {
+            return None;
+        }
         let sum: usize = solution.iter().map(|v| v.round() as usize).sum();
-        return (solution, sum);
+        return Some((solution, sum));
     }
 
     let mut variables = vec![0; matrix.free_indices().len()];
@@ -397,38 +526,144 @@ fn joltage_solve(machine: &Machine) -> (Vec<f64>, usize) {
         &mut best_sum,
         maximum_value,
     );
+    if best_sum == usize::MAX {
+        return None;
+    }
     let matrix_variables: Vec<f64> = best_variables.iter().map(|v| *v as f64).collect();
-    let solution = matrix.solve(&matrix_variables).unwrap();
-    (solution, best_sum)
+    let solution = matrix.solve(&matrix_variables)?;
+    Some((solution, best_sum))
 }
 
-pub fn part_1(input: &str) -> String {
-    let mut sum = 0;
-    for line in input.lines() {
-        let machine = Machine::from(line);
-        sum += machine.solve_lights(1000);
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Parsed = Vec<Machine>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        try_parse(input)
+    }
+
+    // Unsolved when any machine is
+    fn part_1(&self, machines: &Self::Parsed) -> Answer {
+        machines
+            .iter()
+            .map(|machine| machine.solve_lights(1000))
+            .sum::<Option<usize>>()
+            .map_or(Answer::Unsolved, Answer::from)
     }
-    sum.to_string()
-}
 
-pub fn part_2(input: &str) -> String {
-    let mut sum = 0;
-    for (index, line) in input.lines().enumerate() {
-        let machine = Machine::from(line);
-        let (best_solution, best_sum) = joltage_solve(&machine);
-        println!("Solving machine {index}, best_sum={best_sum}");
-        sum += best_sum;
+    fn part_2(&self, machines: &Self::Parsed) -> Answer {
+        machines
+            .iter()
+            .map(|machine| Some(joltage_solve(machine)?.1))
+            .sum::<Option<usize>>()
+            .map_or(Answer::Unsolved, Answer::from)
     }
-    sum.to_string()
+}
+
+pub fn part_1(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day10.part_1(&try_parse(input)?))
+}
+
+pub fn part_2(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day10.part_2(&try_parse(input)?))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::d10::{BUTTON_MASK, Machine, button_joltages};
+    use proptest::prelude::*;
+
+    use crate::{
+        answer::Answer,
+        d10::{
+            BUTTON_MASK, Day10, Machine, button_joltages, joltage_solve, parse_machine, try_parse,
+        },
+        solution::Solution,
+    };
+
+    // Tries every press count up to the smallest requirement a button feeds
+    fn naive_joltage_presses(machine: &Machine) -> Option<usize> {
+        let limits: Vec<u64> = machine
+            .buttons
+            .iter()
+            .map(|button| {
+                button_joltages(*button)
+                    .into_iter()
+                    .map(|index| machine.joltage_requirements[index])
+                    .min()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let mut presses = vec![0; machine.buttons.len()];
+        let mut best = None;
+        loop {
+            let mut joltages = vec![0; machine.joltage_requirements.len()];
+            for (button, count) in machine.buttons.iter().zip(&presses) {
+                for index in button_joltages(*button) {
+                    joltages[index] += count;
+                }
+            }
+            if joltages == machine.joltage_requirements {
+                let sum = presses.iter().sum::<u64>() as usize;
+                best = Some(best.map_or(sum, |best: usize| best.min(sum)));
+            }
+
+            // Count through every combination like an odometer
+            let Some(index) = (0..presses.len()).find(|index| presses[*index] < limits[*index])
+            else {
+                return best;
+            };
+            presses[index] += 1;
+            presses[..index].fill(0);
+        }
+    }
+
+    // Requirements come from pressing random buttons so every machine is solvable
+    fn machine() -> impl Strategy<Value = Machine> {
+        (1usize..=4)
+            .prop_flat_map(|counters| {
+                prop::collection::vec((1u64..(1 << counters), 0u64..4), 1..=4)
+                    .prop_map(move |buttons| (counters, buttons))
+            })
+            .prop_map(|(counters, buttons)| {
+                let mut joltage_requirements = vec![0; counters];
+                for (button, presses) in &buttons {
+                    for index in button_joltages(*button) {
+                        joltage_requirements[index] += presses;
+                    }
+                }
+                Machine {
+                    needed_lights: 0,
+                    buttons: buttons.into_iter().map(|(button, _)| button).collect(),
+                    joltage_requirements,
+                }
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn test_joltage_solve_matches_naive(machine in machine()) {
+            let presses = joltage_solve(&machine).map(|(_, presses)| presses);
+            prop_assert!(presses.is_some());
+            prop_assert_eq!(presses, naive_joltage_presses(&machine));
+        }
+
+        #[test]
+        fn test_joltage_solve_any_requirements(
+            mut machine in machine(),
+            requirements in prop::collection::vec(0u64..6, 4),
+        ) {
+            let counters = machine.joltage_requirements.len();
+            machine.joltage_requirements = requirements[..counters].to_vec();
+            let presses = joltage_solve(&machine).map(|(_, presses)| presses);
+            prop_assert_eq!(presses, naive_joltage_presses(&machine));
+        }
+    }
 
     #[test]
     fn test_expected_machine_definition() {
-        let machine = Machine::from("[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}");
+        let machine = parse_machine(0, "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}").unwrap();
         assert_eq!(machine.needed_lights, 0b0110);
         assert_eq!(machine.buttons.len(), 6);
         assert_eq!(machine.buttons[0], 0b1000);
@@ -442,12 +677,80 @@ mod tests {
 
     #[test]
     fn test_example_machine_light_solutions() {
-        let m1 = Machine::from("[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}");
-        let m2 = Machine::from("[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}");
-        let m3 = Machine::from("[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}");
-        assert_eq!(m1.solve_lights(2), 2);
-        assert_eq!(m2.solve_lights(3), 3);
-        assert_eq!(m3.solve_lights(2), 2);
+        let m1 = parse_machine(0, "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}").unwrap();
+        let m2 = parse_machine(
+            0,
+            "[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}",
+        )
+        .unwrap();
+        let m3 = parse_machine(
+            0,
+            "[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}",
+        )
+        .unwrap();
+        assert_eq!(m1.solve_lights(2), Some(2));
+        assert_eq!(m2.solve_lights(3), Some(3));
+        assert_eq!(m3.solve_lights(2), Some(2));
+        assert_eq!(m2.solve_lights(2), None);
+    }
+
+    #[test]
+    fn test_unsolvable_machines() {
+        // Only light 1 has a button
+        let machines = try_parse("[#.] (1) {0,1}").unwrap();
+        assert_eq!(machines[0].solve_lights(1000), None);
+        assert_eq!(Day10.part_1(&machines), Answer::Unsolved);
+        assert_eq!(
+            Day10.part_1(&try_parse("[..] (1) {0,1}").unwrap()),
+            Answer::from(0usize)
+        );
+
+        // Nothing feeds counter 1
+        let machines = try_parse("[#.] (0) {1,5}").unwrap();
+        assert_eq!(joltage_solve(&machines[0]), None);
+        assert_eq!(Day10.part_2(&machines), Answer::Unsolved);
+        // Counter 1 would need -2 presses of the second button
+        let machines = try_parse("[#.] (0,1) (0) {1,3}").unwrap();
+        assert_eq!(Day10.part_2(&machines), Answer::Unsolved);
+    }
+
+    #[test]
+    fn test_malformed_machine() {
+        let error = try_parse("[.##.] (3) {3,5,4,7}\n[.##.] {3,5}\n")
+            .err()
+            .unwrap();
+        assert_eq!((error.line, error.column), (2, 13));
+
+        let error = try_parse("[.##.] (3) (1,3) {3,x,4,7}").err().unwrap();
+        assert_eq!(error.column, 21);
+
+        let error = try_parse("[.##.] (3) (1,x) {3,5,4,7}").err().unwrap();
+        assert_eq!(
+            (error.column, error.message.as_str()),
+            (15, "expected a light below 4")
+        );
+        let error = try_parse("[.##.] (10) {3,5,4,7}").err().unwrap();
+        assert_eq!(error.column, 9);
+        let error = try_parse("[.##.] 3 {3,5,4,7}").err().unwrap();
+        assert_eq!(error.column, 8);
+        let error = try_parse("[.#x.] (3) {3,5,4,7}").err().unwrap();
+        assert_eq!(
+            (error.column, error.message.as_str()),
+            (4, "expected . or #")
+        );
+        let error = try_parse(".##. (3) {3,5,4,7}").err().unwrap();
+        assert_eq!(error.column, 1);
+
+        let error = try_parse("[.#] (0) (1,2) {1,2}").err().unwrap();
+        assert_eq!(
+            (error.column, error.message.as_str()),
+            (13, "expected a light below 2")
+        );
+        let error = try_parse("[.#] (0) (1) {1,2,3}").err().unwrap();
+        assert_eq!(
+            (error.column, error.message.as_str()),
+            (14, "expected 2 joltages, found 3")
+        );
     }
 
     #[test]
@@ -1,18 +1,83 @@
 use std::collections::HashMap;
 
+use crate::{answer::Answer, error::ParseError, solution::Solution};
+
 type DeviceMap = HashMap<String, Vec<String>>;
 
-fn parse(input: &str) -> HashMap<String, Vec<String>> {
+const DAY: u8 = 11;
+
+pub fn try_parse(input: &str) -> Result<DeviceMap, ParseError> {
     let mut connections = HashMap::new();
-    for line in input.lines() {
-        let (device, rest) = line.split_once(": ").unwrap();
+    let mut lines = HashMap::new();
+    for (index, line) in input.lines().enumerate() {
+        let Some((device, rest)) = line.split_once(": ") else {
+            return Err(ParseError::new(
+                DAY,
+                index,
+                0,
+                line,
+                "expected <device>: <outputs>",
+            ));
+        };
         let outputs = rest
             .split_whitespace()
             .map(|entry| entry.to_string())
             .collect();
         connections.insert(device.into(), outputs);
+        lines.insert(device, (index, line));
     }
-    connections
+    check_acyclic(&connections, &lines)?;
+    Ok(connections)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Visit {
+    OnPath,
+    Done,
+}
+
+// Paths through a cycle never end, so a map with one is rejected. The search keeps its own
+// stack of devices on the current path, long chains would overflow the thread's
+fn check_acyclic(map: &DeviceMap, lines: &HashMap<&str, (usize, &str)>) -> Result<(), ParseError> {
+    let mut starts: Vec<(&str, usize)> = lines
+        .iter()
+        .map(|(device, (index, _))| (*device, *index))
+        .collect();
+    starts.sort_unstable_by_key(|(_, index)| *index);
+
+    let mut visits: HashMap<&str, Visit> = HashMap::new();
+    for (start, _) in starts {
+        if visits.contains_key(start) {
+            continue;
+        }
+        visits.insert(start, Visit::OnPath);
+        let mut stack = vec![(start, 0)];
+        while let Some((device, next)) = stack.last().copied() {
+            let Some(output) = map.get(device).and_then(|outputs| outputs.get(next)) else {
+                visits.insert(device, Visit::Done);
+                stack.pop();
+                continue;
+            };
+            stack.last_mut().unwrap().1 += 1;
+            match visits.get(output.as_str()) {
+                Some(Visit::Done) => {}
+                Some(Visit::OnPath) => {
+                    let (index, line) = lines[device];
+                    let token = line
+                        .split_once(": ")
+                        .and_then(|(_, rest)| rest.split_whitespace().nth(next))
+                        .unwrap();
+                    let message = format!("connection to {output} closes a cycle");
+                    return Err(ParseError::at_token(DAY, index, line, token, message));
+                }
+                None => {
+                    visits.insert(output, Visit::OnPath);
+                    stack.push((output, 0));
+                }
+            }
+        }
+    }
+    Ok(())
 }
 
 fn connections(from: &str, to: &str, map: &DeviceMap, cache: &mut HashMap<String, usize>) -> usize {
@@ -72,18 +137,49 @@ fn connections_with_required_stops(
 
     cache.insert((from.to_string(), dac, fft), result);
 
-    return result;
+    result
 }
 
-pub fn part_1(input: &str) -> String {
-    let map = parse(input);
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Parsed = DeviceMap;
 
-    connections("you", "out", &map, &mut HashMap::new()).to_string()
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        try_parse(input)
+    }
+
+    fn part_1(&self, map: &Self::Parsed) -> Answer {
+        connections("you", "out", map, &mut HashMap::new()).into()
+    }
+
+    fn part_2(&self, map: &Self::Parsed) -> Answer {
+        connections_with_required_stops("svr", false, false, "out", map, &mut HashMap::new()).into()
+    }
+}
+
+pub fn part_1(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day11.part_1(&try_parse(input)?))
+}
+
+pub fn part_2(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day11.part_2(&try_parse(input)?))
 }
 
-pub fn part_2(input: &str) -> String {
-    let map = parse(input);
+#[cfg(test)]
+mod tests {
+    use crate::d11::try_parse;
 
-    connections_with_required_stops("svr", false, false, "out", &map, &mut HashMap::new())
-        .to_string()
+    #[test]
+    fn test_cycle() {
+        let error = try_parse("you: you\n").err().unwrap();
+        assert_eq!((error.line, error.column), (1, 6));
+        assert_eq!(error.message, "connection to you closes a cycle");
+
+        let error = try_parse("you: a\na: b out\nb: c you\n").err().unwrap();
+        assert_eq!((error.line, error.column), (3, 6));
+        assert_eq!(error.message, "connection to you closes a cycle");
+
+        assert!(try_parse("you: a b\na: b out\nb: out\n").is_ok());
+    }
 }
@@ -2,93 +2,82 @@ use itertools::Itertools;
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
-    mem::swap,
 };
 
-type Point = (u8, u8);
+use crate::{
+    answer::Answer,
+    error::ParseError,
+    grid::{Grid, Point},
+    solution::Solution,
+};
+
+// Present shapes and every area with its required count of each shape
+type Puzzles = (Vec<Present>, Vec<(Area, Vec<usize>)>);
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct Present {
-    width: u8,
-    height: u8,
-    parts: Vec<Point>,
+pub struct Present {
+    parts: Grid<bool>,
 }
 
-#[derive(Clone, PartialEq, Eq, Hash)]
-struct Area {
-    width: u8,
-    height: u8,
-    filled: Vec<Point>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Area {
+    width: usize,
+    height: usize,
 }
 
-impl From<&str> for Present {
-    fn from(value: &str) -> Self {
-        let mut width = 0;
-        let mut height = 0;
-        let mut parts = vec![];
-        for (y, line) in value.lines().enumerate() {
-            height = height.max(y as u8 + 1);
-            for (x, char) in line.chars().enumerate() {
-                width = width.max(x as u8 + 1);
-                match char {
-                    '#' => parts.push((x as u8, y as u8)),
-                    '.' => {}
-                    _ => unreachable!(),
-                }
-            }
-        }
+// An area while presents are packed into it, only built for the areas the bounds leave open
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Packing {
+    filled: Grid<bool>,
+}
 
-        Self {
-            width,
-            height,
-            parts,
-        }
-    }
+const DAY: u8 = 12;
+
+// `value` has to be a slice of `input` so errors can point into the whole file
+fn parse_present(input: &str, value: &str) -> Result<Present, ParseError> {
+    let parts = Grid::parse_in(DAY, input, value, |char, _| match char {
+        '#' => Ok(true),
+        '.' => Ok(false),
+        _ => Err("expected # or ."),
+    })?;
+    Ok(Present { parts })
+}
+
+fn render(cells: &Grid<bool>) -> Grid<char> {
+    cells.map(|filled| if *filled { '#' } else { '.' })
 }
 
 impl Display for Present {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                if self.parts.contains(&(x, y)) {
-                    write!(f, "#")?;
-                } else {
-                    write!(f, ".")?;
-                }
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+        write!(f, "{}", render(&self.parts))
     }
 }
 
-impl From<&str> for Area {
-    fn from(value: &str) -> Self {
-        let (left, right) = value.split_once("x").unwrap();
-        Self {
-            width: left.parse().unwrap(),
-            height: right.parse().unwrap(),
-            filled: vec![],
-        }
-    }
+fn parse_area(input: &str, value: &str) -> Result<Area, ParseError> {
+    let Some((left, right)) = value.split_once("x") else {
+        return Err(ParseError::in_input(
+            DAY,
+            input,
+            value,
+            "expected <width>x<height>",
+        ));
+    };
+    let Ok(width) = left.parse() else {
+        return Err(ParseError::in_input(DAY, input, left, "invalid width"));
+    };
+    let Ok(height) = right.parse() else {
+        return Err(ParseError::in_input(DAY, input, right, "invalid height"));
+    };
+    Ok(Area::new(width, height))
 }
 
-impl Display for Area {
+impl Display for Packing {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                match self.filled.contains(&(x, y)) {
-                    true => write!(f, "#")?,
-                    false => write!(f, ".")?,
-                };
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+        write!(f, "{}", render(&self.filled))
     }
 }
 
-fn flip(value: u8, size: u8) -> u8 {
+fn flip(value: usize, size: usize) -> usize {
     size - value - 1
 }
 
@@ -96,37 +85,47 @@ fn diag(point: Point) -> Point {
     (point.1, point.0)
 }
 
-fn flip_x(point: Point, width: u8) -> Point {
+fn flip_x(point: Point, width: usize) -> Point {
     let x = flip(point.0, width);
     let y = point.1;
     (x, y)
 }
 
-fn flip_y(point: Point, height: u8) -> Point {
+fn flip_y(point: Point, height: usize) -> Point {
     let x = point.0;
     let y = flip(point.1, height);
     (x, y)
 }
 
 impl Present {
-    fn flip_x(&mut self) {
-        for index in 0..self.parts.len() {
-            self.parts[index] = flip_x(self.parts[index], self.width);
+    fn size(&self) -> usize {
+        self.parts.iter().filter(|(_, filled)| **filled).count()
+    }
+
+    // Moves every part to `point(part)` on a `width` by `height` grid
+    fn transform(&mut self, width: usize, height: usize, point: impl Fn(Point) -> Point) {
+        let mut parts = Grid::new(width, height, false);
+        for (part, filled) in self.parts.iter() {
+            if *filled {
+                parts[point(part)] = true;
+            }
         }
+        self.parts = parts;
+    }
+
+    fn flip_x(&mut self) {
+        let (width, height) = (self.parts.width(), self.parts.height());
+        self.transform(width, height, |point| flip_x(point, width));
     }
 
     fn flip_y(&mut self) {
-        for index in 0..self.parts.len() {
-            self.parts[index] = flip_y(self.parts[index], self.height);
-        }
+        let (width, height) = (self.parts.width(), self.parts.height());
+        self.transform(width, height, |point| flip_y(point, height));
     }
 
     fn diag_flip(&mut self) {
-        for index in 0..self.parts.len() {
-            self.parts[index] = diag(self.parts[index]);
-        }
-
-        swap(&mut self.width, &mut self.height);
+        let (width, height) = (self.parts.width(), self.parts.height());
+        self.transform(height, width, diag);
     }
 
     fn clockwise_rotate(&mut self) {
@@ -134,6 +133,7 @@ impl Present {
         self.flip_x();
     }
 
+    // `rotations` only turns clockwise
     #[allow(dead_code)]
     fn counter_clockwise_rotate(&mut self) {
         self.diag_flip();
@@ -181,17 +181,22 @@ impl Present {
 }
 
 impl Area {
-    #[allow(dead_code)]
-    fn new(width: u8, height: u8) -> Self {
+    fn new(width: usize, height: usize) -> Self {
+        Self { width, height }
+    }
+}
+
+impl Packing {
+    fn new(area: Area) -> Self {
         Self {
-            width,
-            height,
-            filled: vec![],
+            filled: Grid::new(area.width, area.height, false),
         }
     }
 
-    fn place(&mut self, offset_x: u8, offset_y: u8, present: &Present) -> bool {
-        if offset_x + present.width > self.width || offset_y + present.height > self.height {
+    fn place(&mut self, offset_x: usize, offset_y: usize, present: &Present) -> bool {
+        if offset_x + present.parts.width() > self.filled.width()
+            || offset_y + present.parts.height() > self.filled.height()
+        {
             return false;
         }
         // // Assumption: We only need to test spots that have any filled neighbour
@@ -210,13 +215,15 @@ impl Area {
         //     }
         // }
         let mut next = self.filled.clone();
-        for (part_x, part_y) in &present.parts {
-            let x = part_x + offset_x;
-            let y = part_y + offset_y;
-            if next.contains(&(x, y)) {
+        for ((part_x, part_y), filled) in present.parts.iter() {
+            if !filled {
+                continue;
+            }
+            let point = (part_x + offset_x, part_y + offset_y);
+            if next[point] {
                 return false;
             }
-            next.push((x, y));
+            next[point] = true;
         }
         self.filled = next;
         true
@@ -224,8 +231,8 @@ impl Area {
 
     fn try_place(&mut self, present: &Present) -> bool {
         // TODO: Add outer loop with each variant
-        for offset_y in 0..self.width {
-            for offset_x in 0..self.height {
+        for offset_y in 0..self.filled.height() {
+            for offset_x in 0..self.filled.width() {
                 if self.place(offset_x, offset_y, present) {
                     return true;
                 }
@@ -234,7 +241,7 @@ impl Area {
         false
     }
 
-    fn try_place_variants(&self, variants: &[Present]) -> Vec<Area> {
+    fn try_place_variants(&self, variants: &[Present]) -> Vec<Packing> {
         let mut output = vec![];
         for variant in variants {
             let mut next = self.clone();
@@ -248,20 +255,23 @@ impl Area {
     fn score(&self) -> usize {
         let mut mx = 0;
         let mut my = 0;
-        for (x, y) in self.filled.iter() {
-            mx = mx.max(*x as usize + 1);
-            my = my.max(*y as usize + 1);
+        let mut count = 0;
+        for ((x, y), filled) in self.filled.iter() {
+            if *filled {
+                mx = mx.max(x + 1);
+                my = my.max(y + 1);
+                count += 1;
+            }
         }
-        mx * my - self.filled.len()
+        mx * my - count
     }
 
     fn fit(
         &self,
         presents: &[Present],
         variants_map: &HashMap<Present, Vec<Present>>,
-        cache: &mut HashSet<(Area, Vec<Present>)>,
-        best: &mut usize,
-    ) -> Option<Area> {
+        cache: &mut HashSet<(Packing, Vec<Present>)>,
+    ) -> Option<Packing> {
         if cache.contains(&(self.clone(), presents.to_vec())) {
             return None;
         }
@@ -281,13 +291,6 @@ impl Area {
 
         nexts.sort_unstable_by_key(|a| a.score());
 
-        let previous_best = *best;
-
-        if presents.len() < *best {
-            println!("{self}");
-            *best = presents.len();
-        }
-
         if presents.len() == 1 && !nexts.is_empty() {
             return Some(nexts[0].clone());
         }
@@ -295,20 +298,18 @@ impl Area {
 
         for next in nexts {
             for permutation in rest.iter().cloned().permutations(rest.len()) {
-                if let Some(area) = next.fit(&permutation, variants_map, cache, best) {
-                    return Some(area);
+                if let Some(packing) = next.fit(&permutation, variants_map, cache) {
+                    return Some(packing);
                 };
             }
         }
 
-        *best = previous_best;
-
         cache.insert((self.clone(), presents.to_vec()));
         None
     }
 }
 
-fn parse_input(input: &str) -> (Vec<Present>, Vec<(Area, Vec<usize>)>) {
+pub fn try_parse(input: &str) -> Result<Puzzles, ParseError> {
     let parts = input.split("\n\n");
     let present_strs: Vec<&str> = parts
         .clone()
@@ -318,91 +319,147 @@ fn parse_input(input: &str) -> (Vec<Present>, Vec<(Area, Vec<usize>)>) {
         })
         .collect();
 
-    let puzzles_str = parts.last().unwrap();
+    let puzzles_str = parts.last().unwrap_or_default();
 
-    let presents: Vec<Present> = present_strs.into_iter().map(Present::from).collect();
+    let mut presents = vec![];
+    for present_str in present_strs {
+        presents.push(parse_present(input, present_str)?);
+    }
     let mut area_and_requirements = vec![];
 
     for puzzle_str in puzzles_str.lines() {
-        let (area_str, requirement_str) = puzzle_str.split_once(": ").unwrap();
-        let requirements: Vec<usize> = requirement_str
-            .split_whitespace()
-            .map(|item| item.parse().unwrap())
-            .collect();
-        area_and_requirements.push((Area::from(area_str), requirements));
+        let Some((area_str, requirement_str)) = puzzle_str.split_once(": ") else {
+            let message = "expected <width>x<height>: <counts>";
+            return Err(ParseError::in_input(DAY, input, puzzle_str, message));
+        };
+        let mut requirements = vec![];
+        for item in requirement_str.split_whitespace() {
+            let Ok(count) = item.parse() else {
+                return Err(ParseError::in_input(DAY, input, item, "invalid count"));
+            };
+            if requirements.len() == presents.len() {
+                return Err(ParseError::in_input(
+                    DAY,
+                    input,
+                    item,
+                    "more counts than presents",
+                ));
+            }
+            requirements.push(count);
+        }
+        area_and_requirements.push((parse_area(input, area_str)?, requirements));
     }
 
-    (presents, area_and_requirements)
+    Ok((presents, area_and_requirements))
 }
 
-fn required_presents(types: &[Present], requirements: Vec<usize>) -> Vec<Present> {
+fn required_presents(types: &[Present], requirements: &[usize]) -> Vec<Present> {
     requirements
-        .into_iter()
+        .iter()
         .enumerate()
-        .filter(|(_index, count)| *count > 0)
-        .flat_map(|(index, count)| vec![types[index].clone(); count])
+        .filter(|(index, count)| **count > 0 && types[*index].size() > 0)
+        .flat_map(|(index, count)| vec![types[index].clone(); *count])
         .collect()
 }
 
-pub fn part_1(input: &str) -> String {
-    // let (presents, area_requirements) = parse_input(input);
-    // let mut sum = 0;
-
-    // let mut variants_map = HashMap::new();
-
-    // for present in &presents {
-    //     variants_map.insert(present.clone(), present.variants());
-    // }
-
-    // for (index, (area, requirements)) in area_requirements.into_iter().enumerate() {
-    //     let area_presents = required_presents(&presents, requirements);
-    //     let mut best = usize::MAX;
-    //     if let Some(fitted) = area.fit(
-    //         &area_presents,
-    //         &variants_map,
-    //         &mut HashSet::new(),
-    //         &mut best,
-    //     ) {
-    //         println!("area {index} can fit its requirements");
-    //         println!("{fitted}\n");
-    //         sum += 1;
-    //     }
-    // }
-
-    // sum.to_string()
-    let (presents, area_requirements) = parse_input(input);
-    let mut sum = 0;
-
-    for (area, requirements) in area_requirements.into_iter() {
-        let area_presents = required_presents(&presents, requirements);
-        if area_presents
-            .into_iter()
-            .map(|present| present.parts.len() as u16)
-            .sum::<u16>()
-            <= (area.width as u16 * area.height as u16)
-        {
-            sum += 1;
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Parsed = Puzzles;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        try_parse(input)
+    }
+
+    // An area without room for every cell of its presents never fits them, and one with a
+    // slot as large as the largest present for each of them always does. Only the areas in
+    // between are packed
+    fn part_1(&self, parsed: &Self::Parsed) -> Answer {
+        let (presents, area_requirements) = parsed;
+        let sizes: Vec<usize> = presents.iter().map(Present::size).collect();
+        let slot_width = presents.iter().map(|present| present.parts.width()).max();
+        let slot_height = presents.iter().map(|present| present.parts.height()).max();
+        let (slot_width, slot_height) = (
+            slot_width.unwrap_or(1).max(1),
+            slot_height.unwrap_or(1).max(1),
+        );
+
+        let mut variants_map = HashMap::new();
+        for present in presents {
+            variants_map.insert(present.clone(), present.variants());
         }
+
+        area_requirements
+            .iter()
+            .filter(|(area, requirements)| {
+                // More cells than a usize counts do not fit any area
+                let Some(cells) = requirements
+                    .iter()
+                    .zip(&sizes)
+                    .try_fold(0usize, |cells, (count, size)| {
+                        cells.checked_add(count.checked_mul(*size)?)
+                    })
+                else {
+                    return false;
+                };
+                // An area past usize::MAX cells has room for any count that fits a usize
+                if area
+                    .width
+                    .checked_mul(area.height)
+                    .is_some_and(|room| cells > room)
+                {
+                    return false;
+                }
+                let slots = (area.width / slot_width).checked_mul(area.height / slot_height);
+                // Presents without cells fit anywhere, the others are at most `cells` many
+                let count: usize = requirements
+                    .iter()
+                    .zip(&sizes)
+                    .filter(|(_, size)| **size > 0)
+                    .map(|(count, _)| count)
+                    .sum();
+                if slots.is_none_or(|slots| slots >= count) {
+                    return true;
+                }
+                let area_presents = required_presents(presents, requirements);
+                Packing::new(*area)
+                    .fit(&area_presents, &variants_map, &mut HashSet::new())
+                    .is_some()
+            })
+            .count()
+            .into()
+    }
+
+    fn part_2(&self, _parsed: &Self::Parsed) -> Answer {
+        Answer::NotApplicable
     }
+}
 
-    sum.to_string()
+pub fn part_1(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day12.part_1(&try_parse(input)?))
 }
 
-pub fn part_2(_input: &str) -> String {
-    String::new()
+pub fn part_2(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day12.part_2(&try_parse(input)?))
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        d12::{Area, Present, flip, flip_x, parse_input},
+        answer::Answer,
+        d12::{Area, Day12, Packing, Present, flip, flip_x, parse_present, try_parse},
         input,
+        solution::Solution,
     };
 
+    fn present(value: &str) -> Present {
+        parse_present(value, value).unwrap()
+    }
+
     #[test]
     fn test_flip_twice_is_id() {
-        for value in 1..u8::MAX {
-            for size in (value + 1)..=u8::MAX {
+        for value in 1..u8::MAX as usize {
+            for size in (value + 1)..=u8::MAX as usize {
                 let flipped = flip(value, size);
                 let id = flip(flipped, size);
                 assert_eq!(id, value);
@@ -418,7 +475,7 @@ mod tests {
     #[test]
     fn test_parse_present() {
         let input_str = "###\n#..\n";
-        let p = Present::from(input_str);
+        let p = present(input_str);
         let p_str = format!("{p}");
         assert_eq!(p_str, input_str);
     }
@@ -427,7 +484,7 @@ mod tests {
     fn test_flip_x() {
         let input_str = "###\n#..\n";
         let flip_str = "###\n..#\n";
-        let mut p = Present::from(input_str);
+        let mut p = present(input_str);
         p.flip_x();
         let p_str = format!("{p}");
         assert_eq!(p_str, flip_str);
@@ -437,7 +494,7 @@ mod tests {
     fn test_flip_y() {
         let input_str = "###\n#..\n";
         let flip_str = "#..\n###\n";
-        let mut p = Present::from(input_str);
+        let mut p = present(input_str);
         p.flip_y();
         let p_str = format!("{p}");
         assert_eq!(p_str, flip_str);
@@ -447,7 +504,7 @@ mod tests {
     fn test_diag_flip_id() {
         let input_str = "###\n#..\n";
         let flip_str = "###\n#..\n";
-        let mut p = Present::from(input_str);
+        let mut p = present(input_str);
         p.diag_flip();
         p.diag_flip();
         let p_str = format!("{p}");
@@ -458,7 +515,7 @@ mod tests {
     fn test_cw_rotate() {
         let input_str = "###\n#..\n";
         let flip_str = "##\n.#\n.#\n";
-        let mut p = Present::from(input_str);
+        let mut p = present(input_str);
         p.clockwise_rotate();
         let p_str = format!("{p}");
         assert_eq!(p_str, flip_str);
@@ -467,7 +524,7 @@ mod tests {
     #[test]
     fn test_cw_rotate_id() {
         let input_str = "###\n#..\n";
-        let mut p = Present::from(input_str);
+        let mut p = present(input_str);
         p.clockwise_rotate();
         p.clockwise_rotate();
         p.clockwise_rotate();
@@ -480,7 +537,7 @@ mod tests {
     fn test_ccw_rotate() {
         let input_str = "###\n#..\n";
         let flip_str = "#.\n#.\n##\n";
-        let mut p = Present::from(input_str);
+        let mut p = present(input_str);
         p.counter_clockwise_rotate();
         let p_str = format!("{p}");
         assert_eq!(p_str, flip_str);
@@ -489,8 +546,8 @@ mod tests {
     #[test]
     fn test_place() {
         let input_str = "###\n#..\n";
-        let p = Present::from(input_str);
-        let mut area = Area::new(5, 5);
+        let p = present(input_str);
+        let mut area = Packing::new(Area::new(5, 5));
         let placed = area.place(1, 1, &p);
         assert!(placed)
     }
@@ -498,8 +555,8 @@ mod tests {
     #[test]
     fn test_invalid_place() {
         let input_str = "###\n#..\n";
-        let p = Present::from(input_str);
-        let mut area = Area::new(5, 5);
+        let p = present(input_str);
+        let mut area = Packing::new(Area::new(5, 5));
         let placed = area.place(3, 1, &p);
         assert!(!placed)
     }
@@ -507,8 +564,8 @@ mod tests {
     #[test]
     fn test_try_place() {
         let input_str = "###\n#..\n";
-        let p = Present::from(input_str);
-        let mut area = Area::new(4, 10);
+        let p = present(input_str);
+        let mut area = Packing::new(Area::new(4, 10));
         {
             let placed = area.try_place(&p);
             assert!(placed);
@@ -519,8 +576,8 @@ mod tests {
 
     #[test]
     fn test_second_example() {
-        let (presents, area_requirements) = parse_input(input::D12E);
-        let (mut area, requirements) = area_requirements[1].clone();
+        let (presents, area_requirements) = try_parse(input::D12E).unwrap();
+        let mut area = Packing::new(area_requirements[1].0);
 
         let mut p2 = presents[5].clone();
         p2.clockwise_rotate();
@@ -543,4 +600,30 @@ mod tests {
         assert!(area.try_place(&p5));
         println!("{area}");
     }
+
+    #[test]
+    fn test_large_areas() {
+        let presents = "0:\n###\n#..\n\n1:\n...\n\n";
+        let part_1 = |areas: &str| Day12.part_1(&try_parse(&format!("{presents}{areas}")).unwrap());
+        assert_eq!(part_1("100000x100000: 1 0\n"), Answer::from(1usize));
+        assert_eq!(part_1("4294967296x4294967296: 1 0\n"), Answer::from(1usize));
+        assert_eq!(
+            part_1("3x3: 18446744073709551615 0\n"),
+            Answer::from(0usize)
+        );
+        assert_eq!(
+            part_1("3x3: 1 18446744073709551615\n"),
+            Answer::from(1usize)
+        );
+    }
+
+    #[test]
+    fn test_malformed_present() {
+        let input = "0:\n###\n#x.\n\n4x4: 1\n";
+        let error = try_parse(input).unwrap_err();
+        assert_eq!((error.line, error.column), (3, 2));
+
+        let error = try_parse("0:\n###\n\n4x4: 1 2\n").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 8));
+    }
 }
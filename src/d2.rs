@@ -1,72 +1,116 @@
-use std::ops::RangeInclusive;
+use std::{
+    iter::Sum,
+    ops::{Add, RangeInclusive},
+};
 
-fn parse_ranges(input: &str) -> Vec<RangeInclusive<u64>> {
+use rayon::prelude::*;
+
+use crate::{answer::Answer, d5::combine_ranges, error::ParseError, solution::Solution};
+
+const DAY: u8 = 2;
+
+pub fn try_parse(input: &str) -> Result<Vec<RangeInclusive<u128>>, ParseError> {
+    parse_ranges(input, 10)
+}
+
+// Ranges of ids written in `radix`, 2 to 36 like `u128::from_str_radix`
+pub fn parse_ranges(input: &str, radix: u32) -> Result<Vec<RangeInclusive<u128>>, ParseError> {
+    assert!((2..=36).contains(&radix), "radix={radix} is not in 2..=36");
+    // from_str_radix would also take a sign
+    let parse = |value: &str| {
+        if value.starts_with('+') {
+            None
+        } else {
+            u128::from_str_radix(value, radix).ok()
+        }
+    };
     let mut ranges = vec![];
     for range_str in input.split(',') {
-        let (left_str, right_str) = range_str
-            .trim()
-            .split_once('-')
-            .unwrap_or_else(|| panic!("split by='-' range_str={range_str} failed"));
-
-        let left = left_str
-            .parse::<u64>()
-            .unwrap_or_else(|_| panic!("parse left_str={left_str} to u64 failed"));
-        let right = right_str
-            .parse::<u64>()
-            .unwrap_or_else(|_| panic!("parse right_str={right_str} to u64 failed"));
+        let range_str = range_str.trim();
+        // Ranges may wrap onto the next line and the list may end with a comma
+        if range_str.is_empty() {
+            continue;
+        }
+        let Some((left_str, right_str)) = range_str.split_once('-') else {
+            return Err(ParseError::in_input(
+                DAY,
+                input,
+                range_str,
+                "expected <start>-<end>",
+            ));
+        };
+
+        let Some(left) = parse(left_str) else {
+            return Err(ParseError::in_input(
+                DAY,
+                input,
+                left_str,
+                "invalid range start",
+            ));
+        };
+        let Some(right) = parse(right_str) else {
+            return Err(ParseError::in_input(
+                DAY,
+                input,
+                right_str,
+                "invalid range end",
+            ));
+        };
 
         ranges.push(left..=right);
     }
 
-    ranges
+    Ok(ranges)
 }
 
-fn digit_len(value: u64) -> u32 {
-    value.checked_ilog10().unwrap_or(0) + 1
+pub fn digit_len(value: u128, radix: u32) -> u32 {
+    value.checked_ilog(radix as u128).unwrap_or(0) + 1
 }
 
-fn slice_u64(value: u64, index: u32, length: u32) -> u64 {
-    let digits = digit_len(value);
+// `length` digits of `value` written in `radix`, starting `index` digits from the left
+pub fn slice_digits(value: u128, index: u32, length: u32, radix: u32) -> u128 {
+    let digits = digit_len(value, radix);
 
     if index >= digits {
         return 0;
     }
 
+    let radix = radix as u128;
     let drop_right = digits.saturating_sub(index + length);
-    let mut sliced = value / 10u64.pow(drop_right);
+    let mut sliced = value / radix.pow(drop_right);
 
     if length < digits {
-        sliced %= 10u64.pow(length);
+        sliced %= radix.pow(length);
     }
 
     sliced
 }
 
-fn slice_middle(value: u64) -> (u64, u64) {
-    let digits = digit_len(value);
+pub fn slice_middle(value: u128, radix: u32) -> (u128, u128) {
+    let digits = digit_len(value, radix);
     let half = digits / 2;
 
-    let left = slice_u64(value, 0, half);
-    let right = slice_u64(value, half, half);
+    let left = slice_digits(value, 0, half, radix);
+    let right = slice_digits(value, half, half, radix);
     (left, right)
 }
 
-fn invalid_part_2(input: u64) -> bool {
+pub fn invalid_part_2(input: u128, radix: u32) -> bool {
     if input == 0 {
         return false;
     }
 
-    let digits = input.ilog10() + 1;
+    let digits = digit_len(input, radix);
     let half = digits / 2;
 
     'size: for size in 1..=half {
         if !digits.is_multiple_of(size) {
             continue;
         }
-        let pattern = slice_u64(input, 0, size);
+        let pattern = slice_digits(input, 0, size, radix);
 
         for index in (size..digits).step_by(size as usize) {
-            let needle = slice_u64(input, index, size);
+            let needle = slice_digits(input, index, size, radix);
             if needle != pattern {
                 continue 'size;
             }
@@ -76,64 +120,568 @@ fn invalid_part_2(input: u64) -> bool {
     false
 }
 
-pub fn part_2(input: &str) -> String {
-    let mut sum = 0;
-    for range in parse_ranges(input) {
-        for entry in range {
-            if invalid_part_2(entry) {
-                sum += entry;
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Subtotal {
+    pub count: u128,
+    pub sum: u128,
+}
+
+impl Subtotal {
+    // Wide ranges can sum past u128::MAX
+    pub fn checked_add(self, other: Subtotal) -> Option<Subtotal> {
+        Some(Subtotal {
+            count: self.count.checked_add(other.count)?,
+            sum: self.sum.checked_add(other.sum)?,
+        })
+    }
+
+    // `other` has to count a subset of the ids counted by `self`
+    fn without(self, other: Subtotal) -> Subtotal {
+        Subtotal {
+            count: self.count - other.count,
+            sum: self.sum - other.sum,
+        }
+    }
+}
+
+impl Add for Subtotal {
+    type Output = Subtotal;
+
+    fn add(self, other: Subtotal) -> Subtotal {
+        Subtotal {
+            count: self.count + other.count,
+            sum: self.sum + other.sum,
+        }
+    }
+}
+
+impl Sum for Subtotal {
+    fn sum<I: Iterator<Item = Subtotal>>(iter: I) -> Subtotal {
+        iter.fold(Subtotal::default(), Add::add)
+    }
+}
+
+// Every `digits` long number made of a `unit` long block repeated is `block * multiplier`
+// with `multiplier = 1 + radix^unit + radix^(2 * unit) + ...`, so the ones inside `range`
+// come from a run of consecutive blocks
+fn unit_blocks(
+    range: &RangeInclusive<u128>,
+    radix: u32,
+    digits: u32,
+    unit: u32,
+) -> Option<(u128, RangeInclusive<u128>)> {
+    let radix = radix as u128;
+    // The largest number with `length` digits, radix^length itself can be past u128::MAX
+    let largest = |length: u32| {
+        radix
+            .checked_pow(length)
+            .map_or(u128::MAX, |power| power - 1)
+    };
+    let low = (*range.start()).max(radix.pow(digits - 1));
+    let high = (*range.end()).min(largest(digits));
+    if low > high {
+        return None;
+    }
+    let multiplier = (1..digits / unit).fold(1, |multiplier, _| multiplier * radix.pow(unit) + 1);
+    let first = low.div_ceil(multiplier).max(radix.pow(unit - 1));
+    let last = (high / multiplier).min(largest(unit));
+    (first <= last).then_some((multiplier, first..=last))
+}
+
+// The blocks are an arithmetic series, summed as `first * count + (0 + 1 + ... + count - 1)`.
+// Every product along the way is at most the final sum, so None means the sum does not fit
+fn unit_subtotal(
+    range: &RangeInclusive<u128>,
+    radix: u32,
+    digits: u32,
+    unit: u32,
+) -> Option<Subtotal> {
+    let Some((multiplier, blocks)) = unit_blocks(range, radix, digits, unit) else {
+        return Some(Subtotal::default());
+    };
+    let (first, last) = blocks.into_inner();
+    let count = last - first + 1;
+    let steps = if count.is_multiple_of(2) {
+        (count / 2).checked_mul(count - 1)?
+    } else {
+        count.checked_mul((count - 1) / 2)?
+    };
+    let blocks_sum = first.checked_mul(count)?.checked_add(steps)?;
+    Some(Subtotal {
+        count,
+        sum: blocks_sum.checked_mul(multiplier)?,
+    })
+}
+
+fn prime_factors(mut value: u32) -> Vec<u32> {
+    let mut factors = vec![];
+    let mut factor = 2;
+    while value > 1 {
+        if value.is_multiple_of(factor) {
+            factors.push(factor);
+            while value.is_multiple_of(factor) {
+                value /= factor;
             }
         }
+        factor += 1;
     }
+    factors
+}
 
-    sum.to_string()
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
 }
 
-pub fn part_1(input: &str) -> String {
-    let mut sum = 0;
-    for range in parse_ranges(input) {
-        for entry in range {
-            let digits = digit_len(entry);
-            if digits.is_multiple_of(2) {
-                let (left, right) = slice_middle(entry);
-                if left == right {
-                    sum += entry;
-                }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    // The id is one block repeated exactly this many times, 1111 is 11 twice and 1 four times
+    Exactly(u32),
+    // Any block repeated two or more times
+    AtLeastTwice,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidId {
+    pub id: u128,
+    // For `AtLeastTwice` the shortest block, so the most repeats
+    pub unit: u128,
+    pub repeats: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeReport {
+    pub range: RangeInclusive<u128>,
+    pub ids: Vec<InvalidId>,
+    // None when the sum does not fit a u128
+    pub subtotal: Option<Subtotal>,
+}
+
+impl Rule {
+    pub const PART_1: Rule = Rule::Exactly(2);
+    pub const PART_2: Rule = Rule::AtLeastTwice;
+
+    // The ids in `range` written in `radix`, None when their sum does not fit a u128
+    pub fn subtotal(self, range: &RangeInclusive<u128>, radix: u32) -> Option<Subtotal> {
+        match self {
+            Rule::Exactly(repeats) => (1..=digit_len(*range.end(), radix))
+                .filter(|digits| digits.is_multiple_of(repeats))
+                .try_fold(Subtotal::default(), |total, digits| {
+                    total.checked_add(unit_subtotal(range, radix, digits, digits / repeats)?)
+                }),
+            Rule::AtLeastTwice => repeated_subtotal(range, radix),
+        }
+    }
+
+    // Lists the ids one by one, use `subtotal` for wide ranges
+    pub fn invalid_ids(self, range: &RangeInclusive<u128>, radix: u32) -> Vec<InvalidId> {
+        let mut ids = vec![];
+        for digits in 1..=digit_len(*range.end(), radix) {
+            let repeats: Vec<u32> = match self {
+                Rule::Exactly(repeats) if digits.is_multiple_of(repeats) => vec![repeats],
+                Rule::Exactly(_) => vec![],
+                Rule::AtLeastTwice => (2..=digits)
+                    .filter(|repeats| digits.is_multiple_of(*repeats))
+                    .collect(),
+            };
+            for repeats in repeats {
+                let Some((multiplier, blocks)) =
+                    unit_blocks(range, radix, digits, digits / repeats)
+                else {
+                    continue;
+                };
+                // A block that repeats itself was already listed with its own block
+                let primitive = |block: &u128| {
+                    !matches!(self, Rule::AtLeastTwice) || !invalid_part_2(*block, radix)
+                };
+                ids.extend(blocks.filter(primitive).map(|block| InvalidId {
+                    id: block * multiplier,
+                    unit: block,
+                    repeats,
+                }));
             }
         }
+        ids.sort_unstable_by_key(|invalid| invalid.id);
+        ids
+    }
+}
+
+// Some block repeated at least twice. A block length that works divides `digits / p` for a
+// prime `p` of `digits`, and numbers repeating blocks of two such lengths repeat blocks of
+// their gcd
+fn repeated_subtotal(range: &RangeInclusive<u128>, radix: u32) -> Option<Subtotal> {
+    (2..=digit_len(*range.end(), radix)).try_fold(Subtotal::default(), |total, digits| {
+        let units: Vec<u32> = prime_factors(digits)
+            .iter()
+            .map(|prime| digits / prime)
+            .collect();
+        total.checked_add(union_subtotal(range, radix, digits, &units)?)
+    })
+}
+
+// Ids repeating a block of any of the `units` lengths. Each length adds its ids minus the ones
+// an earlier length already counted, so every step is the subtotal of real ids and only
+// overflows when the whole does, unlike adding and subtracting inclusion-exclusion terms
+fn union_subtotal(
+    range: &RangeInclusive<u128>,
+    radix: u32,
+    digits: u32,
+    units: &[u32],
+) -> Option<Subtotal> {
+    let mut total = Subtotal::default();
+    for (index, unit) in units.iter().enumerate() {
+        let shared: Vec<u32> = units[..index]
+            .iter()
+            .map(|other| gcd(*unit, *other))
+            .collect();
+        let own = unit_subtotal(range, radix, digits, *unit)?;
+        let counted = union_subtotal(range, radix, digits, &shared)?;
+        total = total.checked_add(own.without(counted))?;
+    }
+    Some(total)
+}
+
+// How ids inside several ranges are counted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Overlaps {
+    // Once per range they are in, like the puzzle does
+    #[default]
+    Duplicates,
+    // Once, the ranges are merged first
+    Unique,
+}
+
+fn normalize(ranges: &[RangeInclusive<u128>], overlaps: Overlaps) -> Vec<RangeInclusive<u128>> {
+    match overlaps {
+        Overlaps::Duplicates => ranges.to_vec(),
+        // Empty ranges would stretch whatever they get merged with
+        Overlaps::Unique => combine_ranges(
+            ranges
+                .iter()
+                .filter(|range| !range.is_empty())
+                .cloned()
+                .collect(),
+        ),
     }
+}
 
-    sum.to_string()
+// None when the sum does not fit a u128
+pub fn total(
+    ranges: &[RangeInclusive<u128>],
+    radix: u32,
+    rule: Rule,
+    overlaps: Overlaps,
+) -> Option<Subtotal> {
+    normalize(ranges, overlaps)
+        .par_iter()
+        .map(|range| rule.subtotal(range, radix))
+        .try_reduce(Subtotal::default, Subtotal::checked_add)
+}
+
+// One report per range in input order, or per merged range in ascending order for `Unique`
+pub fn report(
+    ranges: &[RangeInclusive<u128>],
+    radix: u32,
+    rule: Rule,
+    overlaps: Overlaps,
+) -> Vec<RangeReport> {
+    normalize(ranges, overlaps)
+        .into_par_iter()
+        .map(|range| RangeReport {
+            ids: rule.invalid_ids(&range, radix),
+            subtotal: rule.subtotal(&range, radix),
+            range,
+        })
+        .collect()
+}
+
+// Sums of ranges near u64::MAX no longer fit a u64, and wider ranges may not fit a u128
+fn sum_answer(total: Option<Subtotal>) -> Answer {
+    let Some(Subtotal { sum, .. }) = total else {
+        return Answer::Unsolved;
+    };
+    u64::try_from(sum).map_or(Answer::Wide(sum), Answer::Unsigned)
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Parsed = Vec<RangeInclusive<u128>>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        try_parse(input)
+    }
+
+    fn part_1(&self, ranges: &Self::Parsed) -> Answer {
+        sum_answer(total(ranges, 10, Rule::PART_1, Overlaps::Duplicates))
+    }
+
+    fn part_2(&self, ranges: &Self::Parsed) -> Answer {
+        sum_answer(total(ranges, 10, Rule::PART_2, Overlaps::Duplicates))
+    }
+}
+
+pub fn part_1(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day2.part_1(&try_parse(input)?))
+}
+
+pub fn part_2(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day2.part_2(&try_parse(input)?))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::d2::{invalid_part_2, slice_middle};
+    use std::ops::RangeInclusive;
+
+    use proptest::prelude::*;
+
+    use crate::{
+        d2::{
+            InvalidId, Overlaps, Rule, Subtotal, digit_len, invalid_part_2, parse_ranges, report,
+            slice_digits, slice_middle, total, try_parse,
+        },
+        input::example,
+    };
+
+    // Checks every id like the original solution did
+    fn brute_subtotal(range: RangeInclusive<u128>, invalid: impl Fn(u128) -> bool) -> Subtotal {
+        range
+            .filter(|id| invalid(*id))
+            .map(|id| Subtotal { count: 1, sum: id })
+            .sum()
+    }
+
+    fn invalid_part_1(id: u128) -> bool {
+        digit_len(id, 10).is_multiple_of(2) && {
+            let (left, right) = slice_middle(id, 10);
+            left == right
+        }
+    }
+
+    fn repeats_exactly(id: u128, repeats: u32, radix: u32) -> bool {
+        let digits = digit_len(id, radix);
+        let unit = digits / repeats;
+        digits.is_multiple_of(repeats)
+            && (1..repeats).all(|index| {
+                slice_digits(id, index * unit, unit, radix) == slice_digits(id, 0, unit, radix)
+            })
+    }
+
+    fn follows(rule: Rule, id: u128, radix: u32) -> bool {
+        match rule {
+            Rule::Exactly(repeats) => repeats_exactly(id, repeats, radix),
+            Rule::AtLeastTwice => invalid_part_2(id, radix),
+        }
+    }
+
+    #[test]
+    fn test_report_example() {
+        let ranges = try_parse(example(2, 1).unwrap()).unwrap();
+        let reports = report(&ranges, 10, Rule::PART_2, Overlaps::Duplicates);
+        assert_eq!(reports.len(), ranges.len());
+        assert_eq!(
+            reports[1].ids,
+            vec![
+                InvalidId {
+                    id: 99,
+                    unit: 9,
+                    repeats: 2
+                },
+                InvalidId {
+                    id: 111,
+                    unit: 1,
+                    repeats: 3
+                },
+            ]
+        );
+        assert_eq!(reports[1].subtotal.unwrap().sum, 210);
+        let total: Subtotal = reports.iter().map(|report| report.subtotal.unwrap()).sum();
+        assert_eq!(total.sum, 4174379265);
+
+        let reports = report(&ranges, 10, Rule::PART_1, Overlaps::Duplicates);
+        assert_eq!(reports[1].ids.len(), 1);
+        let reports = report(&ranges, 10, Rule::Exactly(3), Overlaps::Duplicates);
+        assert_eq!(reports[1].ids[0].id, 111);
+        assert_eq!(reports[2].ids[0].id, 999);
+    }
+
+    #[test]
+    fn test_overlapping_ranges() {
+        let ranges = try_parse("95-115,100-1200,1010-1010,30-20").unwrap();
+        let duplicates = total(&ranges, 10, Rule::PART_2, Overlaps::Duplicates).unwrap();
+        let unique = total(&ranges, 10, Rule::PART_2, Overlaps::Unique).unwrap();
+        assert_eq!(Some(unique), Rule::PART_2.subtotal(&(95..=1200), 10));
+        assert_eq!(duplicates.count, unique.count + 2);
+        assert_eq!(duplicates.sum, unique.sum + 111 + 1010);
+
+        let reports = report(&ranges, 10, Rule::PART_1, Overlaps::Unique);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].range, 95..=1200);
+    }
 
     #[test]
     fn test_slice_middle() {
-        assert_eq!(slice_middle(11), (1, 1));
-        assert_eq!(slice_middle(1111), (11, 11));
-        assert_eq!(slice_middle(110011), (110, 11));
-        assert_eq!(slice_middle(11011011), (1101, 1011));
+        assert_eq!(slice_middle(11, 10), (1, 1));
+        assert_eq!(slice_middle(1111, 10), (11, 11));
+        assert_eq!(slice_middle(110011, 10), (110, 11));
+        assert_eq!(slice_middle(11011011, 10), (1101, 1011));
     }
 
     #[test]
     fn test_invalid_part_2() {
-        assert!(invalid_part_2(11));
-        assert!(invalid_part_2(22));
-        assert!(invalid_part_2(99));
-        assert!(invalid_part_2(1010));
-        assert!(invalid_part_2(1188511885));
-        assert!(invalid_part_2(222222));
-        assert!(invalid_part_2(446446));
-        assert!(invalid_part_2(38593859));
+        assert!(invalid_part_2(11, 10));
+        assert!(invalid_part_2(22, 10));
+        assert!(invalid_part_2(99, 10));
+        assert!(invalid_part_2(1010, 10));
+        assert!(invalid_part_2(1188511885, 10));
+        assert!(invalid_part_2(222222, 10));
+        assert!(invalid_part_2(446446, 10));
+        assert!(invalid_part_2(38593859, 10));
     }
 
     #[test]
     fn test_valid_part_2() {
         for value in 1698522..=1698528 {
-            assert!(!invalid_part_2(value));
+            assert!(!invalid_part_2(value, 10));
+        }
+    }
+
+    #[test]
+    fn test_other_radixes() {
+        assert_eq!(digit_len(u128::MAX, 2), 128);
+        assert_eq!(digit_len(u128::MAX, 10), 39);
+        assert_eq!(slice_middle(0b1011_0110, 2), (0b1011, 0b0110));
+        assert!(invalid_part_2(0b101101, 2));
+        assert!(!invalid_part_2(0b101100, 2));
+        assert!(invalid_part_2(0xabcabc, 16));
+        assert!(invalid_part_2(u128::MAX, 2));
+        assert!(invalid_part_2(u128::MAX, 16));
+        assert!(!invalid_part_2(u128::MAX, 10));
+    }
+
+    #[test]
+    fn test_parse_radix() {
+        assert_eq!(
+            parse_ranges("ff-1ff,\nABC-abd,", 16).unwrap(),
+            vec![0xff..=0x1ff, 0xabc..=0xabd]
+        );
+        let error = parse_ranges("101-111,10-12", 2).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 12));
+        assert!(parse_ranges("+1-10", 10).is_err());
+    }
+
+    #[test]
+    fn test_total_other_radixes() {
+        // 0x11 to 0xff, 0x11 times 1 to 15
+        let ranges = parse_ranges("0-ff", 16).unwrap();
+        assert_eq!(
+            total(&ranges, 16, Rule::PART_1, Overlaps::Duplicates),
+            Some(Subtotal {
+                count: 15,
+                sum: 17 * 120
+            })
+        );
+
+        let ranges = parse_ranges("101-11011,1010-111111", 2).unwrap();
+        for rule in [Rule::PART_1, Rule::Exactly(3), Rule::PART_2] {
+            let brute = ranges
+                .iter()
+                .map(|range| brute_subtotal(range.clone(), |id| follows(rule, id, 2)))
+                .sum();
+            assert_eq!(total(&ranges, 2, rule, Overlaps::Duplicates), Some(brute));
+        }
+    }
+
+    #[test]
+    fn test_malformed_range() {
+        let error = try_parse("11-22,95-115,\n998-x012").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 5));
+        assert_eq!(error.text, "998-x012");
+    }
+
+    #[test]
+    fn test_subtotals_example_ranges() {
+        let range = 1188511880..=1188511890;
+        assert_eq!(
+            Rule::PART_1.subtotal(&range, 10),
+            Some(Subtotal {
+                count: 1,
+                sum: 1188511885
+            })
+        );
+        assert_eq!(
+            Rule::PART_2.subtotal(&(95..=115), 10),
+            Some(Subtotal {
+                count: 2,
+                sum: 99 + 111
+            })
+        );
+    }
+
+    #[test]
+    fn test_subtotals_up_to_u64_max() {
+        let range = 0..=u64::MAX as u128;
+        let halves = Rule::PART_1.subtotal(&range, 10).unwrap();
+        // Every half of 1 to 9 digits, then 20 digit ids up to 1844674407 1844674407 <= u64::MAX
+        assert_eq!(
+            halves.count,
+            999_999_999 + (1_844_674_407 - 1_000_000_000 + 1)
+        );
+        let repeated = Rule::PART_2.subtotal(&range, 10).unwrap();
+        assert!(repeated.count > halves.count && repeated.sum > halves.sum);
+    }
+
+    #[test]
+    fn test_subtotals_up_to_u128_max() {
+        let top = u128::MAX..=u128::MAX;
+        for radix in [2, 16] {
+            assert_eq!(
+                Rule::PART_2.subtotal(&top, radix),
+                Some(Subtotal {
+                    count: 1,
+                    sum: u128::MAX
+                })
+            );
+        }
+        assert_eq!(Rule::PART_2.subtotal(&top, 10), Some(Subtotal::default()));
+        assert_eq!(Rule::PART_1.subtotal(&(0..=u128::MAX), 2), None);
+        assert_eq!(
+            Rule::Exactly(1).subtotal(&(1..=2), 10),
+            Some(Subtotal { count: 2, sum: 3 })
+        );
+        assert_eq!(
+            Rule::Exactly(1).subtotal(&(u128::MAX - 1..=u128::MAX), 10),
+            None
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_subtotals_match_brute_force(start in 0u128..2_000_000, length in 0u128..3_000) {
+            let range = start..=start + length;
+            prop_assert_eq!(Rule::PART_1.subtotal(&range, 10), Some(brute_subtotal(range.clone(), invalid_part_1)));
+            prop_assert_eq!(Rule::PART_2.subtotal(&range, 10), Some(brute_subtotal(range, |id| invalid_part_2(id, 10))));
+        }
+
+        #[test]
+        fn test_invalid_ids_match_subtotal(
+            start in 0u128..2_000_000,
+            length in 0u128..3_000,
+            repeats in 2u32..=4,
+            radix in prop::sample::select(vec![2u32, 3, 10, 16]),
+        ) {
+            let range = start..=start + length;
+            for rule in [Rule::Exactly(repeats), Rule::AtLeastTwice] {
+                let ids = rule.invalid_ids(&range, radix);
+                let brute: Vec<u128> = range.clone().filter(|id| follows(rule, *id, radix)).collect();
+                prop_assert_eq!(ids.iter().map(|invalid| invalid.id).collect::<Vec<_>>(), brute);
+                for invalid in &ids {
+                    prop_assert!(invalid.unit > 0);
+                    prop_assert!(repeats_exactly(invalid.id, invalid.repeats, radix));
+                    prop_assert_eq!(digit_len(invalid.unit, radix) * invalid.repeats, digit_len(invalid.id, radix));
+                }
+                let sum: u128 = ids.iter().map(|invalid| invalid.id).sum();
+                prop_assert_eq!(rule.subtotal(&range, radix), Some(Subtotal { count: ids.len() as u128, sum }));
+            }
         }
     }
 }
@@ -1,95 +1,621 @@
-fn joltage_from_char(value: char) -> u64 {
-    match value {
-        '0' => 0,
-        '1' => 1,
-        '2' => 2,
-        '3' => 3,
-        '4' => 4,
-        '5' => 5,
-        '6' => 6,
-        '7' => 7,
-        '8' => 8,
-        '9' => 9,
-        _ => 0,
-    }
-}
-
-fn parse_max_with_min_rem(bank: &[u64], min_rem: usize) -> (u64, &[u64]) {
-    let size = bank.len();
-    let mut max = 0;
-    let mut max_index = 0;
-    for (index, value) in bank.iter().enumerate() {
-        if size - index <= min_rem {
+use std::fmt::Display;
+
+use crate::{answer::Answer, error::ParseError, solution::Solution};
+
+const DAY: u8 = 3;
+
+fn joltage_from_char(value: char, radix: u32) -> Option<u64> {
+    value.to_digit(radix).map(u64::from)
+}
+
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JoltageValue {
+    Narrow(u64),
+    Wide(u128),
+    // More digits than a u128 holds, already written in the radix
+    Digits(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Joltage {
+    pub radix: u32,
+    pub value: JoltageValue,
+}
+
+// A rating that is not a digit in the radix the joltage is read in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RatingError {
+    pub index: usize,
+    pub rating: u64,
+    pub radix: u32,
+}
+
+impl Display for RatingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "rating={} at index={} is not a base {} digit",
+            self.rating, self.index, self.radix
+        )
+    }
+}
+
+impl std::error::Error for RatingError {}
+
+fn check_ratings(ratings: &[u64], radix: u32) -> Result<(), RatingError> {
+    match ratings.iter().position(|rating| *rating >= radix as u64) {
+        Some(index) => Err(RatingError {
+            index,
+            rating: ratings[index],
+            radix,
+        }),
+        None => Ok(()),
+    }
+}
+
+impl Joltage {
+    // `digits` are read in `radix`, so every one of them has to be below it
+    pub fn from_digits(digits: &[u64], radix: u32) -> Result<Self, RatingError> {
+        check_ratings(digits, radix)?;
+        Ok(Self::from_checked_digits(digits, radix))
+    }
+
+    fn from_checked_digits(digits: &[u64], radix: u32) -> Self {
+        let narrow = digits.iter().try_fold(0u64, |joltage, digit| {
+            joltage.checked_mul(radix as u64)?.checked_add(*digit)
+        });
+        if let Some(joltage) = narrow {
+            return Self {
+                radix,
+                value: JoltageValue::Narrow(joltage),
+            };
+        }
+        let wide = digits.iter().try_fold(0u128, |joltage, digit| {
+            joltage
+                .checked_mul(radix as u128)?
+                .checked_add(*digit as u128)
+        });
+        let value = match wide {
+            Some(joltage) => JoltageValue::Wide(joltage),
+            None => JoltageValue::Digits(
+                digits
+                    .iter()
+                    .map(|digit| char::from(DIGITS[*digit as usize]))
+                    .collect(),
+            ),
+        };
+        Self { radix, value }
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.value {
+            JoltageValue::Narrow(joltage) => Some(joltage),
+            _ => None,
+        }
+    }
+}
+
+// Lowercase like `u128::from_str_radix` reads them
+fn to_radix(mut value: u128, radix: u32) -> String {
+    let radix = radix as u128;
+    let mut digits = vec![];
+    loop {
+        digits.push(char::from(DIGITS[(value % radix) as usize]));
+        value /= radix;
+        if value == 0 {
             break;
         }
-        if *value > max {
-            max = *value;
-            max_index = index;
+    }
+    digits.iter().rev().collect()
+}
+
+// Written in the radix the ratings were read in
+impl Display for Joltage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.value {
+            JoltageValue::Narrow(joltage) => f.pad(&to_radix(*joltage as u128, self.radix)),
+            JoltageValue::Wide(joltage) => f.pad(&to_radix(*joltage, self.radix)),
+            JoltageValue::Digits(digits) => f.pad(digits),
         }
     }
+}
 
-    (max, &bank[max_index + 1..])
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    // Batteries turned on, ascending
+    pub indices: Vec<usize>,
+    pub joltage: Joltage,
 }
 
-fn bank_from_line(input: &str) -> Vec<u64> {
-    input.chars().map(joltage_from_char).collect()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    Max,
+    Min,
 }
 
-pub fn part_1(input: &str) -> String {
-    let mut sum_joltage = 0;
-    for line in input.lines() {
-        let bank = bank_from_line(line);
+impl Goal {
+    fn prefers(self, value: u64, other: u64) -> bool {
+        match self {
+            Goal::Max => value > other,
+            Goal::Min => value < other,
+        }
+    }
+}
 
-        // for (index, char) in line.chars().enumerate() {
-        //     let last = index + 1 == length;
-        //     let joltage = joltage_from_char(char);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Objective {
+    pub goal: Goal,
+    pub digits: usize,
+    // Turned on batteries at most this many positions apart, 1 keeps them next to each other
+    pub max_gap: Option<usize>,
+    pub no_adjacent: bool,
+}
 
-        //     if joltage > max_one && !last {
-        //         max_one = joltage;
-        //         max_two = 0;
-        //     } else if joltage > max_two {
-        //         max_two = joltage
-        //     }
-        // }
+impl Objective {
+    pub fn max(digits: usize) -> Self {
+        Self {
+            goal: Goal::Max,
+            digits,
+            max_gap: None,
+            no_adjacent: false,
+        }
+    }
 
-        let (max_one, bank_rem) = parse_max_with_min_rem(&bank, 1);
-        let (max_two, _) = parse_max_with_min_rem(bank_rem, 0);
+    pub fn min(digits: usize) -> Self {
+        Self {
+            goal: Goal::Min,
+            ..Self::max(digits)
+        }
+    }
+}
 
-        let bank_joltage = max_one * 10 + max_two;
-        sum_joltage += bank_joltage;
+// Largest number formed by keeping `digits` batteries in order, None when the bank is shorter
+pub fn select_max(bank: &[u64], digits: usize) -> Result<Option<Selection>, RatingError> {
+    select(bank, &Objective::max(digits), 10)
+}
+
+// None when no choice of batteries meets the objective. The batteries are compared by rating,
+// `radix` only has to be above every rating to read the joltage
+pub fn select(
+    bank: &[u64],
+    objective: &Objective,
+    radix: u32,
+) -> Result<Option<Selection>, RatingError> {
+    check_ratings(bank, radix)?;
+    Ok(select_checked(bank, objective, radix))
+}
+
+fn select_checked(bank: &[u64], objective: &Objective, radix: u32) -> Option<Selection> {
+    if objective.max_gap.is_none() && !objective.no_adjacent {
+        return select_stack(bank, objective.digits, objective.goal, radix);
     }
+    select_constrained(bank, objective, radix)
+}
 
-    sum_joltage.to_string()
+// A battery pops worse ones before it off the stack while enough batteries remain to refill
+// it, so every battery is pushed and popped at most once
+fn select_stack(bank: &[u64], digits: usize, goal: Goal, radix: u32) -> Option<Selection> {
+    if digits > bank.len() {
+        return None;
+    }
+    let mut stack: Vec<usize> = Vec::with_capacity(digits);
+    for (index, value) in bank.iter().enumerate() {
+        while let Some(&top) = stack.last() {
+            let remaining = bank.len() - index;
+            if !goal.prefers(*value, bank[top]) || stack.len() - 1 + remaining < digits {
+                break;
+            }
+            stack.pop();
+        }
+        if stack.len() < digits {
+            stack.push(index);
+        }
+    }
+    Some(selection(bank, stack, radix))
 }
 
-pub fn part_2(input: &str) -> String {
-    let mut sum_joltage = 0;
-    for line in input.lines() {
-        let bank = bank_from_line(line);
-        let mut bank_rem: &[u64] = &bank;
-        let mut bank_joltage = 0;
+// Picks the best digit reachable from every position that spelled the best prefix so far,
+// only stepping onto batteries that can still be followed by enough others
+fn select_constrained(bank: &[u64], objective: &Objective, radix: u32) -> Option<Selection> {
+    let Objective {
+        goal,
+        digits,
+        max_gap,
+        no_adjacent,
+    } = *objective;
+    let len = bank.len();
+    if digits > len {
+        return None;
+    }
+    if digits == 0 {
+        return Some(selection(bank, vec![], radix));
+    }
+    let min_step = if no_adjacent { 2 } else { 1 };
+    let max_step = max_gap.unwrap_or(len);
+    let next = |index: usize| index + min_step..=(index + max_step).min(len - 1);
 
-        for position in (0..12).rev() {
-            let (value, rem) = parse_max_with_min_rem(bank_rem, position);
-            bank_rem = rem;
+    // fits[count - 1][index]: `count` batteries can be turned on starting at `index`
+    let mut fits = vec![vec![true; len]];
+    for count in 1..digits {
+        let row = (0..len)
+            .map(|index| next(index).any(|next| fits[count - 1][next]))
+            .collect();
+        fits.push(row);
+    }
 
-            let joltage_value = value * 10u64.pow(position as u32);
-            bank_joltage += joltage_value;
+    // Every position still in the running with the index of a parent in the previous layer
+    let mut layers: Vec<Vec<(usize, usize)>> = vec![];
+    let mut candidates: Vec<(usize, usize)> = (0..len)
+        .filter(|index| fits[digits - 1][*index])
+        .map(|index| (index, 0))
+        .collect();
+    for remaining in (0..digits).rev() {
+        let best = candidates
+            .iter()
+            .map(|(index, _)| bank[*index])
+            .reduce(|best, value| {
+                if goal.prefers(value, best) {
+                    value
+                } else {
+                    best
+                }
+            })?;
+        candidates.retain(|(index, _)| bank[*index] == best);
+        layers.push(candidates);
+        if remaining == 0 {
+            break;
         }
+        let mut seen = vec![false; len];
+        candidates = vec![];
+        for (parent, (index, _)) in layers.last().unwrap().iter().enumerate() {
+            for next in next(*index) {
+                if fits[remaining - 1][next] && !seen[next] {
+                    seen[next] = true;
+                    candidates.push((next, parent));
+                }
+            }
+        }
+    }
+
+    // Every surviving path spells the same digits
+    let mut indices = vec![];
+    let mut position = 0;
+    for layer in layers.iter().rev() {
+        let (index, parent) = layer[position];
+        indices.push(index);
+        position = parent;
+    }
+    indices.reverse();
+    Some(selection(bank, indices, radix))
+}
+
+fn selection(bank: &[u64], indices: Vec<usize>, radix: u32) -> Selection {
+    let values: Vec<u64> = indices.iter().map(|index| bank[*index]).collect();
+    Selection {
+        joltage: Joltage::from_checked_digits(&values, radix),
+        indices,
+    }
+}
+
+// Unsolved when a bank is too short or its joltage does not fit a u64
+fn total_joltage(banks: &[Vec<u64>], digits: usize) -> Answer {
+    banks
+        .iter()
+        .map(|bank| select_stack(bank, digits, Goal::Max, 10)?.joltage.to_u64())
+        .sum::<Option<u64>>()
+        .map_or(Answer::Unsolved, Answer::from)
+}
+
+fn bank_from_line(index: usize, line: &str, radix: u32) -> Result<Vec<u64>, ParseError> {
+    // Would otherwise only show up as a bank too short to select from
+    if line.is_empty() {
+        return Err(ParseError::new(DAY, index, 0, line, "empty bank"));
+    }
+    line.char_indices()
+        .map(|(column, value)| {
+            joltage_from_char(value, radix).ok_or_else(|| {
+                ParseError::new(
+                    DAY,
+                    index,
+                    column,
+                    line,
+                    format!("invalid joltage rating, expected a base {radix} digit"),
+                )
+            })
+        })
+        .collect()
+}
+
+pub fn try_parse(input: &str) -> Result<Vec<Vec<u64>>, ParseError> {
+    parse_banks(input, 10)
+}
+
+// Banks with ratings written in `radix`, 16 allows `a` to `f`
+pub fn parse_banks(input: &str, radix: u32) -> Result<Vec<Vec<u64>>, ParseError> {
+    assert!((2..=36).contains(&radix), "radix={radix} is not in 2..=36");
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| bank_from_line(index, line, radix))
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BankReport {
+    // Zero based line of the bank in the input
+    pub line: usize,
+    pub bank: Vec<u64>,
+    // One per objective, in the order they were given
+    pub selections: Vec<Option<Selection>>,
+}
+
+pub fn report(
+    input: &str,
+    radix: u32,
+    objectives: &[Objective],
+) -> Result<Vec<BankReport>, ParseError> {
+    let banks = parse_banks(input, radix)?;
+    let reports = banks
+        .into_iter()
+        .enumerate()
+        .map(|(line, bank)| {
+            let selections = objectives
+                .iter()
+                // Parsing already checked the ratings against `radix`
+                .map(|objective| select_checked(&bank, objective, radix))
+                .collect();
+            BankReport {
+                line,
+                bank,
+                selections,
+            }
+        })
+        .collect();
+    Ok(reports)
+}
+
+pub struct Day3;
 
-        sum_joltage += bank_joltage;
+impl Solution for Day3 {
+    type Parsed = Vec<Vec<u64>>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        try_parse(input)
+    }
+
+    fn part_1(&self, banks: &Self::Parsed) -> Answer {
+        total_joltage(banks, 2)
     }
 
-    sum_joltage.to_string()
+    fn part_2(&self, banks: &Self::Parsed) -> Answer {
+        total_joltage(banks, 12)
+    }
+}
+
+pub fn part_1(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day3.part_1(&try_parse(input)?))
+}
+
+pub fn part_2(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day3.part_2(&try_parse(input)?))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::d3::parse_max_with_min_rem;
+    use proptest::prelude::*;
+
+    use crate::d3::{
+        Goal, Joltage, JoltageValue, Objective, RatingError, Selection, parse_banks, report,
+        select, select_max, try_parse,
+    };
+
+    // Tries every way of keeping `digits` batteries
+    fn naive_max_joltage(bank: &[u64], digits: u32) -> u64 {
+        let mut best = 0;
+        for mask in 0u32..(1 << bank.len()) {
+            if mask.count_ones() != digits {
+                continue;
+            }
+            let joltage = bank
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| mask & (1 << index) != 0)
+                .fold(0, |joltage, (_, value)| joltage * 10 + value);
+            best = best.max(joltage);
+        }
+        best
+    }
+
+    fn meets(indices: &[usize], objective: &Objective) -> bool {
+        indices.windows(2).all(|pair| {
+            let gap = pair[1] - pair[0];
+            objective.max_gap.is_none_or(|max_gap| gap <= max_gap)
+                && !(objective.no_adjacent && gap == 1)
+        })
+    }
+
+    // Tries every way of keeping `digits` batteries that meets the constraints
+    fn naive_select(bank: &[u64], objective: &Objective) -> Option<u64> {
+        (0u32..(1 << bank.len()))
+            .filter(|mask| mask.count_ones() as usize == objective.digits)
+            .map(|mask| {
+                (0..bank.len())
+                    .filter(|index| mask & (1 << index) != 0)
+                    .collect::<Vec<usize>>()
+            })
+            .filter(|indices| meets(indices, objective))
+            .map(|indices| {
+                indices
+                    .iter()
+                    .fold(0, |joltage, index| joltage * 10 + bank[*index])
+            })
+            .reduce(|best, joltage| match objective.goal {
+                Goal::Max => best.max(joltage),
+                Goal::Min => best.min(joltage),
+            })
+    }
+
+    fn objective() -> impl Strategy<Value = Objective> {
+        (
+            prop_oneof![Just(Goal::Max), Just(Goal::Min)],
+            1usize..=6,
+            prop::option::of(1usize..=4),
+            any::<bool>(),
+        )
+            .prop_map(|(goal, digits, max_gap, no_adjacent)| Objective {
+                goal,
+                digits,
+                max_gap,
+                no_adjacent,
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn test_select_matches_naive(
+            bank in prop::collection::vec(0u64..10, 1..=12),
+            objective in objective(),
+        ) {
+            let selection = select(&bank, &objective, 10).unwrap();
+            let naive = naive_select(&bank, &objective);
+            prop_assert_eq!(selection.is_some(), naive.is_some());
+            if let (Some(selection), Some(naive)) = (selection, naive) {
+                prop_assert_eq!(selection.joltage.value, JoltageValue::Narrow(naive));
+                prop_assert!(meets(&selection.indices, &objective));
+            }
+        }
+
+        #[test]
+        fn test_select_max_matches_naive(
+            (bank, digits) in prop::collection::vec(0u64..10, 1..=14)
+                .prop_flat_map(|bank| {
+                    let len = bank.len() as u32;
+                    (Just(bank), 1..=len)
+                })
+        ) {
+            let selection = select_max(&bank, digits as usize).unwrap().unwrap();
+            let naive = naive_max_joltage(&bank, digits);
+            prop_assert_eq!(&selection.joltage.value, &JoltageValue::Narrow(naive));
+            prop_assert!(selection.indices.is_sorted_by(|a, b| a < b));
+            let picked: Vec<u64> = selection.indices.iter().map(|index| bank[*index]).collect();
+            prop_assert_eq!(Joltage::from_digits(&picked, 10), Ok(selection.joltage));
+        }
+    }
+
+    #[test]
+    fn test_select_max() {
+        let bank = [8, 1, 8, 1, 8, 1, 9, 1, 1, 1, 1, 2, 1, 1, 1];
+        assert_eq!(
+            select_max(&bank, 2),
+            Ok(Some(Selection {
+                indices: vec![6, 11],
+                joltage: Joltage {
+                    radix: 10,
+                    value: JoltageValue::Narrow(92),
+                },
+            }))
+        );
+        assert_eq!(select_max(&bank, 16), Ok(None));
+    }
+
+    #[test]
+    fn test_report_objectives() {
+        let objectives = [
+            Objective::max(2),
+            Objective::min(2),
+            Objective {
+                max_gap: Some(1),
+                ..Objective::max(2)
+            },
+            Objective {
+                no_adjacent: true,
+                ..Objective::max(3)
+            },
+        ];
+        let reports = report("818181911112111\n12\n", 10, &objectives).unwrap();
+        assert_eq!(reports.len(), 2);
+        let indices: Vec<Option<Vec<usize>>> = reports[0]
+            .selections
+            .iter()
+            .map(|selection| {
+                selection
+                    .as_ref()
+                    .map(|selection| selection.indices.clone())
+            })
+            .collect();
+        assert_eq!(
+            indices,
+            vec![
+                Some(vec![6, 11]),
+                Some(vec![1, 3]),
+                Some(vec![6, 7]),
+                Some(vec![6, 11, 13]),
+            ]
+        );
+        assert_eq!(reports[1].line, 1);
+        assert_eq!(reports[1].selections[3], None);
+    }
+
+    #[test]
+    fn test_wide_joltage() {
+        let bank = [9; 60];
+        let joltage = |digits| select_max(&bank, digits).unwrap().unwrap().joltage;
+        assert_eq!(joltage(19).value, JoltageValue::Narrow(10u64.pow(19) - 1));
+        assert_eq!(joltage(25).value, JoltageValue::Wide(10u128.pow(25) - 1));
+        assert_eq!(joltage(50).value, JoltageValue::Digits("9".repeat(50)));
+        assert_eq!(joltage(25).to_string(), "9".repeat(25));
+    }
+
+    #[test]
+    fn test_invalid_rating() {
+        let error = try_parse("987654321111111\n81118x111119\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 6));
+        assert_eq!(error.text, "81118x111119");
+        assert!(parse_banks("12ab", 10).is_err());
+
+        let error = try_parse("12\n\n34").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.message, "empty bank");
+    }
+
+    #[test]
+    fn test_hex_banks() {
+        let banks = parse_banks("1f0a9\nABC\n", 16).unwrap();
+        assert_eq!(banks, vec![vec![1, 15, 0, 10, 9], vec![10, 11, 12]]);
+        let selection = select(&banks[0], &Objective::max(2), 16).unwrap().unwrap();
+        assert_eq!(selection.indices, vec![1, 3]);
+        assert_eq!(selection.joltage.value, JoltageValue::Narrow(0xfa));
+        assert_eq!(selection.joltage.to_string(), "fa");
+        let selection = select(&banks[0], &Objective::min(3), 16).unwrap().unwrap();
+        assert_eq!(selection.joltage.value, JoltageValue::Narrow(0x0a9));
+        assert_eq!(selection.joltage.to_string(), "a9");
+
+        let wide = Joltage::from_digits(&[15; 20], 16).unwrap();
+        assert_eq!(wide.value, JoltageValue::Wide(u128::MAX >> 48));
+        assert_eq!(wide.to_string(), "f".repeat(20));
+        let digits = Joltage::from_digits(&[15; 40], 16).unwrap();
+        assert_eq!(digits.value, JoltageValue::Digits("f".repeat(40)));
+        assert_eq!(digits.to_string(), "f".repeat(40));
+        assert_eq!(
+            Joltage::from_digits(&[1, 0, 1], 2).unwrap().to_string(),
+            "101"
+        );
+    }
 
     #[test]
-    fn test_parse_max_with_min_rem() {
-        assert_eq!(parse_max_with_min_rem(&[1, 2, 3, 4], 2), (2, &[3, 4][..]));
-        assert_eq!(parse_max_with_min_rem(&[1, 2, 3, 4], 0), (4, &[][..]));
+    fn test_ratings_above_radix() {
+        let error = RatingError {
+            index: 1,
+            rating: 15,
+            radix: 10,
+        };
+        assert_eq!(
+            select(&[1, 15, 0], &Objective::max(2), 10),
+            Err(error.clone())
+        );
+        assert_eq!(select_max(&[1, 15, 0], 5), Err(error));
+        assert!(Joltage::from_digits(&[2], 2).is_err());
     }
 }
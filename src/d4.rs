@@ -1,107 +1,260 @@
-type Grid = Vec<Vec<bool>>;
+use crate::{answer::Answer, error::ParseError, grid::Grid, solution::Solution};
 
-fn parse_roll(input: u8) -> bool {
-    input == b'@'
-}
+const DAY: u8 = 4;
 
-fn parse_grid(input: &str) -> Grid {
-    let mut grid = vec![];
-    for line_str in input.lines() {
-        let mut row = vec![];
-        for byte in line_str.bytes() {
-            row.push(parse_roll(byte));
-        }
-        grid.push(row);
+const WORD: usize = u64::BITS as usize;
+
+fn parse_roll(input: char) -> Result<bool, &'static str> {
+    match input {
+        '@' => Ok(true),
+        '.' => Ok(false),
+        _ => Err("expected @ or ."),
     }
-    grid
 }
 
-fn roll_at(x: usize, y: usize, grid: &Grid) -> bool {
-    let Some(row) = grid.get(y) else {
-        return false;
-    };
-    let Some(cell) = row.get(x) else {
-        return false;
-    };
-    *cell
+// One bit per cell, bit `x % 64` of word `x / 64` of a row. Rows are padded to whole words
+// and the padding is always clear, so shifted rows never carry in rolls that are not there
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rolls {
+    width: usize,
+    height: usize,
+    words: usize,
+    bits: Vec<u64>,
 }
 
-fn adjacent(x: usize, y: usize) -> Vec<(usize, usize)> {
-    let range_x = x.saturating_sub(1)..=(x + 1);
-    let range_y = y.saturating_sub(1)..=(y + 1);
-    let mut values = vec![];
-
-    for r_y in range_y {
-        for r_x in range_x.clone() {
-            if r_y == y && r_x == x {
-                continue;
+impl From<&Grid<bool>> for Rolls {
+    fn from(grid: &Grid<bool>) -> Self {
+        let words = grid.width().div_ceil(WORD);
+        let mut bits = vec![0; words * grid.height()];
+        for ((x, y), roll) in grid.iter() {
+            if *roll {
+                bits[y * words + x / WORD] |= 1 << (x % WORD);
             }
-            values.push((r_x, r_y));
+        }
+        Rolls {
+            width: grid.width(),
+            height: grid.height(),
+            words,
+            bits,
         }
     }
+}
 
-    values
+pub fn try_parse(input: &str) -> Result<Rolls, ParseError> {
+    let grid = Grid::parse(DAY, input, |char, _| parse_roll(char))?;
+    Ok(Rolls::from(&grid))
 }
 
-fn adjacent_rolls(x: usize, y: usize, grid: &Grid) -> usize {
-    let mut counter = 0;
-    for (x, y) in adjacent(x, y) {
-        if roll_at(x, y, grid) {
-            counter += 1;
-        }
-    }
-    counter
+// Bit-sliced counters, bit `i` of `ones`, `twos`, `fours` and `eights` together hold the
+// count for cell `i` of a word
+#[derive(Default)]
+struct Counter {
+    ones: u64,
+    twos: u64,
+    fours: u64,
+    eights: u64,
 }
 
-fn removable(x: usize, y: usize, grid: &Grid) -> bool {
-    adjacent_rolls(x, y, grid) < 4
+impl Counter {
+    fn add(&mut self, mask: u64) {
+        let carry = self.ones & mask;
+        self.ones ^= mask;
+        let carry_twos = self.twos & carry;
+        self.twos ^= carry;
+        let carry_fours = self.fours & carry_twos;
+        self.fours ^= carry_twos;
+        self.eights |= carry_fours;
+    }
+
+    fn below_four(&self) -> u64 {
+        !(self.fours | self.eights)
+    }
 }
 
-pub fn part_1(input: &str) -> String {
-    let grid = parse_grid(input);
-    let mut counter = 0;
+impl Rolls {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn count(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    fn row(&self, y: usize) -> &[u64] {
+        &self.bits[y * self.words..(y + 1) * self.words]
+    }
 
-    for (y, row) in grid.iter().enumerate() {
-        for (x, cell) in row.iter().enumerate() {
-            if *cell && removable(x, y, &grid) {
-                counter += 1;
+    // Rolls of row `y` with fewer than four rolls around them, one word at a time
+    fn removable(&self, y: usize, removable: &mut [u64]) {
+        let above = y.checked_sub(1).map(|y| self.row(y));
+        let below = (y + 1 < self.height).then(|| self.row(y + 1));
+        let own = self.row(y);
+        for (index, removable) in removable.iter_mut().enumerate() {
+            let mut counter = Counter::default();
+            // The cells left and right of every cell in the word, pulling in the edge bits
+            // of the neighbouring words
+            let sides = |row: &[u64]| {
+                let word = row[index];
+                let previous = index.checked_sub(1).map_or(0, |index| row[index]);
+                let next = row.get(index + 1).copied().unwrap_or(0);
+                (
+                    (word << 1) | (previous >> (WORD - 1)),
+                    (word >> 1) | (next << (WORD - 1)),
+                )
+            };
+            for row in [above, below].into_iter().flatten() {
+                let (left, right) = sides(row);
+                counter.add(left);
+                counter.add(row[index]);
+                counter.add(right);
             }
+            let (left, right) = sides(own);
+            counter.add(left);
+            counter.add(right);
+            *removable = own[index] & counter.below_four();
         }
     }
 
-    counter.to_string()
+    // Removing a roll only lowers the counts around it, so peeling in any order ends with
+    // the same rolls. Rows are revisited only while a row next to them lost rolls
+    fn peel(&mut self) -> usize {
+        let mut removed = 0;
+        let mut removable = vec![0; self.words];
+        let mut dirty = vec![true; self.height];
+        loop {
+            let mut next_dirty = vec![false; self.height];
+            let mut changed = false;
+            for y in (0..self.height).filter(|y| dirty[*y]) {
+                self.removable(y, &mut removable);
+                if removable.iter().all(|word| *word == 0) {
+                    continue;
+                }
+                let row = &mut self.bits[y * self.words..(y + 1) * self.words];
+                for (word, removable) in row.iter_mut().zip(&removable) {
+                    removed += removable.count_ones() as usize;
+                    *word &= !removable;
+                }
+                let rows = y.saturating_sub(1)..(y + 2).min(self.height);
+                next_dirty[rows].fill(true);
+                changed = true;
+            }
+            if !changed {
+                return removed;
+            }
+            dirty = next_dirty;
+        }
+    }
 }
 
-pub fn part_2(input: &str) -> String {
-    let mut grid = parse_grid(input);
-    let mut counter = 0;
+pub struct Day4;
 
-    let mut queue = vec![];
+impl Solution for Day4 {
+    type Parsed = Rolls;
 
-    for (y, row) in grid.iter().enumerate() {
-        for (x, cell) in row.iter().enumerate() {
-            if *cell {
-                queue.push((x, y));
-            }
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        try_parse(input)
+    }
+
+    fn part_1(&self, rolls: &Self::Parsed) -> Answer {
+        let mut removable = vec![0; rolls.words];
+        let mut counter: usize = 0;
+
+        for y in 0..rolls.height {
+            rolls.removable(y, &mut removable);
+            counter += removable
+                .iter()
+                .map(|word| word.count_ones() as usize)
+                .sum::<usize>();
         }
+
+        counter.into()
     }
 
-    loop {
-        let Some((x, y)) = queue.pop() else {
-            break;
-        };
+    fn part_2(&self, rolls: &Self::Parsed) -> Answer {
+        rolls.clone().peel().into()
+    }
+}
 
-        if roll_at(x, y, &grid) && removable(x, y, &grid) {
-            counter += 1;
-            grid[y][x] = false;
+pub fn part_1(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day4.part_1(&try_parse(input)?))
+}
 
-            for (x, y) in adjacent(x, y) {
-                if roll_at(x, y, &grid) {
-                    queue.push((x, y));
-                }
+pub fn part_2(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day4.part_2(&try_parse(input)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::{
+        answer::Answer,
+        d4::{Day4, try_parse},
+        grid::{Grid, Point},
+        solution::Solution,
+    };
+
+    fn removable(point: Point, grid: &Grid<bool>) -> bool {
+        grid.neighbours_8(point)
+            .filter(|neighbour| grid[*neighbour])
+            .count()
+            < 4
+    }
+
+    // Cell by cell on a plain grid, removing rolls one at a time
+    fn naive_parts(grid: &Grid<bool>) -> (usize, usize) {
+        let part_1 = grid
+            .iter()
+            .filter(|(point, cell)| **cell && removable(*point, grid))
+            .count();
+
+        let mut grid = grid.clone();
+        let mut part_2 = 0;
+        let mut queue: Vec<Point> = grid
+            .iter()
+            .filter(|(_, cell)| **cell)
+            .map(|(point, _)| point)
+            .collect();
+        while let Some(point) = queue.pop() {
+            if grid[point] && removable(point, &grid) {
+                part_2 += 1;
+                grid[point] = false;
+                queue.extend(grid.neighbours_8(point).filter(|point| grid[*point]));
             }
         }
+        (part_1, part_2)
+    }
+
+    proptest! {
+        #[test]
+        fn test_rolls_match_naive(
+            (width, cells) in (1usize..=140, 1usize..=8).prop_flat_map(|(width, height)| {
+                (Just(width), prop::collection::vec(prop::bool::weighted(0.65), width * height))
+            })
+        ) {
+            let grid = Grid::from_fn(width, cells.len() / width, |(x, y)| cells[y * width + x]);
+            let input = grid.map(|roll| if *roll { '@' } else { '.' }).to_string();
+            let rolls = try_parse(&input).unwrap();
+            prop_assert_eq!(rolls.count(), cells.iter().filter(|cell| **cell).count());
+
+            let (part_1, part_2) = naive_parts(&grid);
+            prop_assert_eq!(Day4.part_1(&rolls), Answer::from(part_1));
+            prop_assert_eq!(Day4.part_2(&rolls), Answer::from(part_2));
+        }
     }
 
-    counter.to_string()
+    #[test]
+    fn test_malformed_rolls() {
+        let error = try_parse("@.@\n.x.\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        let error = try_parse("@.@\n..\n").unwrap_err();
+        assert_eq!(error.message, "row is 2 wide, expected 3");
+    }
 }
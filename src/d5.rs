@@ -1,38 +1,100 @@
-use std::ops::RangeInclusive;
+use std::ops::{RangeInclusive, Sub};
 
-fn parse_id_ranges(input: &str) -> Vec<RangeInclusive<u64>> {
+use crate::{answer::Answer, error::ParseError, solution::Solution};
+
+const DAY: u8 = 5;
+
+fn parse_id_range(index: usize, line: &str) -> Result<RangeInclusive<u64>, ParseError> {
+    let Some((left, right)) = line.split_once('-') else {
+        return Err(ParseError::new(
+            DAY,
+            index,
+            0,
+            line,
+            "expected <start>-<end>",
+        ));
+    };
+    let Ok(start) = left.parse::<u64>() else {
+        return Err(ParseError::at_token(
+            DAY,
+            index,
+            line,
+            left,
+            "invalid range start",
+        ));
+    };
+    let Ok(end) = right.parse::<u64>() else {
+        return Err(ParseError::at_token(
+            DAY,
+            index,
+            line,
+            right,
+            "invalid range end",
+        ));
+    };
+    if start > end {
+        return Err(ParseError::at_token(
+            DAY,
+            index,
+            line,
+            right,
+            "range end is before its start",
+        ));
+    }
+    Ok(start..=end)
+}
+
+// Fresh id ranges come first, then a blank line and the available ids
+pub fn try_parse(input: &str) -> Result<(Vec<RangeInclusive<u64>>, Vec<u64>), ParseError> {
     let mut ranges = vec![];
-    for line in input.lines() {
-        let Some((left, right)) = line.split_once('-') else {
-            continue;
-        };
-        let Ok(start) = left.parse::<u64>() else {
+    let mut ids = vec![];
+    let mut separated = false;
+
+    for (index, line) in input.lines().enumerate() {
+        if !separated {
+            if line.is_empty() {
+                separated = true;
+            } else {
+                ranges.push(parse_id_range(index, line)?);
+            }
             continue;
-        };
-        let Ok(end) = right.parse::<u64>() else {
+        }
+        if line.is_empty() {
             continue;
+        }
+        let Ok(id) = line.parse() else {
+            return Err(ParseError::new(DAY, index, 0, line, "invalid id"));
         };
-        ranges.push(start..=end);
+        ids.push(id);
     }
-    ranges
-}
 
-fn parse_ids(input: &str) -> Vec<u64> {
-    input.lines().flat_map(|line| line.parse()).collect()
-}
+    if !separated {
+        let index = input.lines().count().saturating_sub(1);
+        let line = input.lines().last().unwrap_or_default();
+        return Err(ParseError::new(
+            DAY,
+            index,
+            line.len(),
+            line,
+            "expected a blank line before the ids",
+        ));
+    }
 
-fn split_input(input: &str) -> (&str, &str) {
-    input.split_once("\n\n").expect("empty line")
+    Ok((ranges, ids))
 }
 
-fn combine_range(a: &RangeInclusive<u64>, b: &RangeInclusive<u64>) -> Option<RangeInclusive<u64>> {
+fn combine_range<T>(a: &RangeInclusive<T>, b: &RangeInclusive<T>) -> Option<RangeInclusive<T>>
+where
+    T: Copy + Ord + Sub<Output = T> + From<u8>,
+{
     let a_start = *a.start();
     let a_end = *a.end();
     let b_start = *b.start();
     let b_end = *b.end();
     let max_start = a_start.max(b_start);
     let max_end = a_end.min(b_end);
-    if max_start <= max_end || max_start == max_end + 1 {
+    // Subtracting leaves room for ranges ending at the largest id
+    if max_start <= max_end || max_start - max_end == T::from(1) {
         let new_start = a_start.min(b_start);
         let new_end = a_end.max(b_end);
         Some(new_start..=new_end)
@@ -41,7 +103,11 @@ fn combine_range(a: &RangeInclusive<u64>, b: &RangeInclusive<u64>) -> Option<Ran
     }
 }
 
-fn combine_ranges(ranges: Vec<RangeInclusive<u64>>) -> Vec<RangeInclusive<u64>> {
+// Generic so day 2 can merge its u128 ranges the same way
+pub(crate) fn combine_ranges<T>(ranges: Vec<RangeInclusive<T>>) -> Vec<RangeInclusive<T>>
+where
+    T: Copy + Ord + Sub<Output = T> + From<u8>,
+{
     if ranges.is_empty() {
         return Vec::new();
     }
@@ -61,35 +127,105 @@ fn combine_ranges(ranges: Vec<RangeInclusive<u64>>) -> Vec<RangeInclusive<u64>>
     combined_ranges
 }
 
-pub fn part_1(input: &str) -> String {
-    let (input_1, input_2) = split_input(input);
-    let ranges = parse_id_ranges(input_1);
-    let ids = parse_ids(input_2);
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Parsed = (Vec<RangeInclusive<u64>>, Vec<u64>);
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        try_parse(input)
+    }
+
+    fn part_1(&self, parsed: &Self::Parsed) -> Answer {
+        let (ranges, ids) = parsed;
 
-    let mut counter = 0;
+        let mut counter: usize = 0;
 
-    'id_search: for id in ids {
-        for range in ranges.clone() {
-            if range.contains(&id) {
-                counter += 1;
-                continue 'id_search;
+        'id_search: for id in ids {
+            for range in ranges {
+                if range.contains(id) {
+                    counter += 1;
+                    continue 'id_search;
+                }
             }
         }
+
+        counter.into()
+    }
+
+    fn part_2(&self, parsed: &Self::Parsed) -> Answer {
+        let (ranges, _) = parsed;
+        let ranges = combine_ranges(ranges.clone());
+
+        // 0 to u64::MAX holds one id more than a u64 counts
+        let mut id_counter: u128 = 0;
+
+        for range in ranges {
+            let size = (*range.end() - *range.start()) as u128 + 1;
+            id_counter += size;
+        }
+
+        u64::try_from(id_counter).map_or(Answer::Wide(id_counter), Answer::Unsigned)
     }
+}
+
+pub fn part_1(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day5.part_1(&try_parse(input)?))
+}
 
-    counter.to_string()
+pub fn part_2(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day5.part_2(&try_parse(input)?))
 }
 
-pub fn part_2(input: &str) -> String {
-    let (input_1, _) = split_input(input);
-    let ranges = combine_ranges(parse_id_ranges(input_1));
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, ops::RangeInclusive};
 
-    let mut id_counter = 0;
+    use proptest::prelude::*;
 
-    for range in ranges {
-        let size = *range.end() - *range.start() + 1;
-        id_counter += size;
+    use crate::{
+        answer::Answer,
+        d5::{Day5, combine_ranges, try_parse},
+        solution::Solution,
+    };
+
+    // Marks every id and reads back the maximal runs
+    fn naive_combine_ranges(ranges: &[RangeInclusive<u64>]) -> Vec<RangeInclusive<u64>> {
+        let ids: BTreeSet<u64> = ranges.iter().flat_map(|range| range.clone()).collect();
+        let mut combined: Vec<RangeInclusive<u64>> = vec![];
+        for id in ids {
+            match combined.last_mut() {
+                Some(last) if *last.end() + 1 == id => *last = *last.start()..=id,
+                _ => combined.push(id..=id),
+            }
+        }
+        combined
     }
 
-    id_counter.to_string()
+    proptest! {
+        #[test]
+        fn test_combine_ranges_matches_naive(
+            ranges in prop::collection::vec(
+                (0u64..60, 0u64..8).prop_map(|(start, length)| start..=start + length),
+                0..12,
+            )
+        ) {
+            prop_assert_eq!(combine_ranges(ranges.clone()), naive_combine_ranges(&ranges));
+        }
+    }
+
+    #[test]
+    fn test_reversed_range() {
+        let error = try_parse("3-5\n20-10\n\n1\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.message, "range end is before its start");
+    }
+
+    #[test]
+    fn test_full_range() {
+        let parsed = try_parse("0-18446744073709551615\n5-10\n\n").unwrap();
+        assert_eq!(Day5.part_2(&parsed), Answer::Wide(1 << 64));
+        let parsed = try_parse("18446744073709551615-18446744073709551615\n\n").unwrap();
+        assert_eq!(Day5.part_2(&parsed), Answer::Unsigned(1));
+    }
 }
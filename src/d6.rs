@@ -1,3 +1,7 @@
+use crate::{answer::Answer, error::ParseError, solution::Solution};
+
+const DAY: u8 = 6;
+
 type Expression = (Vec<u64>, bool);
 
 fn parse_operations(input: &str) -> Vec<bool> {
@@ -40,31 +44,29 @@ fn parse_numbers(input: &str) -> Vec<Vec<u64>> {
     problems
 }
 
-fn eval(expression: Expression) -> u64 {
+// None once a result no longer fits in a u128
+fn eval(expression: &Expression) -> Option<u128> {
     let (values, operation) = expression;
+    let mut values = values.iter().map(|&value| u128::from(value));
     match operation {
-        true => values.iter().sum(),
-        false => values.iter().product(),
+        true => values.try_fold(0, u128::checked_add),
+        false => values.try_fold(1, u128::checked_mul),
     }
 }
 
-pub fn part_1(input: &str) -> String {
-    let numbers = parse_numbers(input);
-    let operations = parse_operations(input);
-    let mut sum: u64 = 0;
-
-    assert!(numbers.len() == operations.len());
-
-    let problems = numbers.into_iter().zip(operations);
-
-    for (values, operation) in problems {
-        sum += eval((values, operation));
-    }
-
-    sum.to_string()
+// Unsolved when the grand total overflows, Wide when it only fits in a u128
+fn grand_total(expressions: &[Expression]) -> Answer {
+    expressions
+        .iter()
+        .try_fold(0, |sum: u128, expression| {
+            sum.checked_add(eval(expression)?)
+        })
+        .map_or(Answer::Unsolved, |sum| {
+            u64::try_from(sum).map_or(Answer::Wide(sum), Answer::Unsigned)
+        })
 }
 
-pub fn cephalopod_from_lines(input: &str) -> Vec<Expression> {
+pub fn cephalopod_from_lines(input: &str) -> Result<Vec<Expression>, ParseError> {
     let mut formatted = vec![];
 
     for line in input.lines() {
@@ -85,9 +87,8 @@ pub fn cephalopod_from_lines(input: &str) -> Vec<Expression> {
     let mut operation = true;
     let mut parsed = vec![];
 
-    formatted.reverse();
-
-    for line in &mut formatted {
+    // Problems are read right to left, one number per column
+    for (x, line) in formatted.iter_mut().enumerate().rev() {
         if line.trim().is_empty() {
             continue;
         }
@@ -101,21 +102,139 @@ pub fn cephalopod_from_lines(input: &str) -> Vec<Expression> {
             group_done = true;
             operation = false;
         }
-        values.push(line.parse().unwrap());
+        let Ok(value) = line.parse() else {
+            let first = input.lines().next().unwrap_or_default();
+            return Err(ParseError::new(DAY, 0, x, first, "column without a number"));
+        };
+        values.push(value);
         if group_done {
             parsed.push((values.clone(), operation));
             values.clear();
         }
     }
 
-    parsed
+    Ok(parsed)
+}
+
+// Every line holds digits aligned in columns, except the last which holds the operators
+fn validate(input: &str) -> Result<(), ParseError> {
+    let lines: Vec<&str> = input.lines().collect();
+    let Some((operator_line, number_lines)) = lines.split_last() else {
+        return Err(ParseError::new(DAY, 0, 0, "", "empty worksheet"));
+    };
+    for (y, line) in number_lines.iter().enumerate() {
+        if let Some((x, _)) = line
+            .char_indices()
+            .find(|(_, char)| !char.is_ascii_digit() && *char != ' ')
+        {
+            return Err(ParseError::new(DAY, y, x, line, "expected a digit"));
+        }
+    }
+    if let Some((x, _)) = operator_line
+        .char_indices()
+        .find(|(_, char)| !matches!(char, '+' | '*' | ' '))
+    {
+        let y = number_lines.len();
+        return Err(ParseError::new(DAY, y, x, operator_line, "expected + or *"));
+    }
+    Ok(())
 }
 
-pub fn part_2(input: &str) -> String {
-    let mut sum = 0;
-    for (values, operation) in cephalopod_from_lines(input) {
-        let result = eval((values.clone(), operation));
-        sum += result;
+pub fn try_parse(input: &str) -> Result<Worksheet, ParseError> {
+    let input = input.trim_end_matches(['\n', '\r']);
+    validate(input)?;
+
+    let numbers = parse_numbers(input);
+    let operations = parse_operations(input);
+
+    if numbers.len() != operations.len() {
+        let y = input.lines().count() - 1;
+        let line = input.lines().last().unwrap_or_default();
+        let message = format!(
+            "expected {} operators, found {}",
+            numbers.len(),
+            operations.len()
+        );
+        return Err(ParseError::new(DAY, y, line.len(), line, message));
+    }
+
+    Ok(Worksheet {
+        human: numbers.into_iter().zip(operations).collect(),
+        cephalopod: cephalopod_from_lines(input)?,
+    })
+}
+
+pub struct Worksheet {
+    human: Vec<Expression>,
+    cephalopod: Vec<Expression>,
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Parsed = Worksheet;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        try_parse(input)
+    }
+
+    fn part_1(&self, worksheet: &Self::Parsed) -> Answer {
+        grand_total(&worksheet.human)
+    }
+
+    fn part_2(&self, worksheet: &Self::Parsed) -> Answer {
+        grand_total(&worksheet.cephalopod)
+    }
+}
+
+pub fn part_1(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day6.part_1(&try_parse(input)?))
+}
+
+pub fn part_2(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day6.part_2(&try_parse(input)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        answer::Answer,
+        d6::{Day6, try_parse},
+        solution::Solution,
+    };
+
+    #[test]
+    fn test_unknown_operator() {
+        let error = try_parse("12 3\n4  5\n+  -\n").err().unwrap();
+        assert_eq!((error.line, error.column), (3, 4));
+        assert_eq!(error.message, "expected + or *");
+
+        let error = try_parse("12 3\n4  x\n+  *\n").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.message, "expected a digit");
+    }
+
+    #[test]
+    fn test_ragged_operator_row() {
+        let error = try_parse("12 3\n4  5\n+\n").err().unwrap();
+        assert_eq!((error.line, error.column), (3, 2));
+        assert_eq!(error.message, "expected 2 operators, found 1");
+
+        let error = try_parse("12 3\n4  5\n+  * +\n").err().unwrap();
+        assert_eq!((error.line, error.column), (3, 7));
+        assert_eq!(error.message, "expected 2 operators, found 3");
+    }
+
+    #[test]
+    fn test_wide_products() {
+        let worksheet = try_parse("99999999\n99999999\n99999999\n*\n").unwrap();
+        assert_eq!(
+            Day6.part_1(&worksheet),
+            Answer::Wide(999_999_970_000_000_299_999_999)
+        );
+
+        let rows = "99999999\n".repeat(5);
+        let worksheet = try_parse(&format!("{rows}*\n")).unwrap();
+        assert_eq!(Day6.part_1(&worksheet), Answer::Unsolved);
     }
-    sum.to_string()
 }
@@ -1,10 +1,23 @@
 use std::collections::HashMap;
 
-type Row = Vec<char>;
-type Grid = Vec<Row>;
+use crate::{answer::Answer, error::ParseError, grid::Grid, solution::Solution};
 
-fn parse_grid(input: &str) -> Grid {
-    input.lines().map(|line| line.chars().collect()).collect()
+type Row = [char];
+
+const DAY: u8 = 7;
+
+pub fn try_parse(input: &str) -> Result<Grid<char>, ParseError> {
+    let grid = Grid::parse(DAY, input, |char, (_, y)| match char {
+        '.' | '^' => Ok(char),
+        // The beam enters from the top, a start further down has no meaning
+        'S' if y == 0 => Ok(char),
+        'S' => Err("S outside the first row"),
+        _ => Err("expected ., S or ^"),
+    })?;
+    if grid.height() == 0 {
+        return Err(ParseError::new(DAY, 0, 0, "", "empty manifold"));
+    }
+    Ok(grid)
 }
 
 fn extend_beam(row: &mut Row, index: usize) -> usize {
@@ -35,18 +48,18 @@ fn eval_step(current: &Row, next: &mut Row) -> usize {
     splits
 }
 
-fn eval_grid(grid: &mut Grid) -> usize {
+fn eval_grid(grid: &mut Grid<char>) -> usize {
     let mut splits = 0;
-    for index in 0..(grid.len() - 1) {
-        let current = grid[index].clone();
-        let next = &mut grid[index + 1];
+    for index in 0..(grid.height() - 1) {
+        let current = grid.row(index).to_vec();
+        let next = grid.row_mut(index + 1);
         splits += eval_step(&current, next);
     }
     splits
 }
 
 fn paths_from(
-    grid: &Grid,
+    grid: &Grid<char>,
     y: usize,
     x: usize,
     cache: &mut HashMap<(usize, usize), usize>,
@@ -55,11 +68,11 @@ fn paths_from(
         return *cached;
     }
 
-    if y + 1 == grid.len() {
+    if y + 1 == grid.height() {
         return 1;
     }
 
-    let next = grid[y][x];
+    let next = grid[(x, y)];
 
     match next {
         'S' | '.' => paths_from(grid, y + 1, x, cache),
@@ -68,7 +81,7 @@ fn paths_from(
             if x > 0 {
                 sum += paths_from(grid, y + 1, x - 1, cache);
             }
-            if x + 1 < grid.len() {
+            if x + 1 < grid.width() {
                 sum += paths_from(grid, y + 1, x + 1, cache);
             }
             cache.insert((y, x), sum);
@@ -78,11 +91,11 @@ fn paths_from(
     }
 }
 
-fn walk_grid(grid: &Grid) -> usize {
-    if grid.is_empty() {
+fn walk_grid(grid: &Grid<char>) -> usize {
+    if grid.height() == 0 {
         return 0;
     }
-    let first_row = &grid[0];
+    let first_row = grid.row(0);
 
     let Some((start, _)) = first_row.iter().enumerate().find(|(_, char)| **char == 'S') else {
         return 0;
@@ -91,13 +104,30 @@ fn walk_grid(grid: &Grid) -> usize {
     paths_from(grid, 0, start, &mut HashMap::new())
 }
 
-pub fn part_1(input: &str) -> String {
-    let mut grid = parse_grid(input);
-    let splits = eval_grid(&mut grid);
-    splits.to_string()
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Parsed = Grid<char>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        try_parse(input)
+    }
+
+    fn part_1(&self, grid: &Self::Parsed) -> Answer {
+        let mut grid = grid.clone();
+        let splits = eval_grid(&mut grid);
+        splits.into()
+    }
+
+    fn part_2(&self, grid: &Self::Parsed) -> Answer {
+        walk_grid(grid).into()
+    }
+}
+
+pub fn part_1(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day7.part_1(&try_parse(input)?))
 }
 
-pub fn part_2(input: &str) -> String {
-    let grid = parse_grid(input);
-    walk_grid(&grid).to_string()
+pub fn part_2(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day7.part_2(&try_parse(input)?))
 }
@@ -1,3 +1,5 @@
+use crate::{answer::Answer, error::ParseError, solution::Solution};
+
 type Circuit = Vec<usize>;
 
 type JunctionBox = (u64, u64, u64);
@@ -15,21 +17,40 @@ fn distance_squared(a: JunctionBox, b: JunctionBox) -> u64 {
     dx.pow(2) + dy.pow(2) + dz.pow(2)
 }
 
-fn parse_junction_boxes(input: &str) -> Vec<JunctionBox> {
+const DAY: u8 = 8;
+
+fn parse_junction_boxes(input: &str) -> Result<Vec<JunctionBox>, ParseError> {
     let mut output = vec![];
-    for line in input.lines() {
+    for (index, line) in input.lines().enumerate() {
         let parts: Vec<&str> = line.splitn(3, ',').collect();
-        let left = parts[0];
-        let middle = parts[1];
-        let right = parts[2];
-
-        let x = left.parse().unwrap();
-        let y = middle.parse().unwrap();
-        let z = right.parse().unwrap();
+        let [left, middle, right] = parts[..] else {
+            return Err(ParseError::new(
+                DAY,
+                index,
+                line.len(),
+                line,
+                "expected x,y,z",
+            ));
+        };
+
+        let mut coordinates = [0; 3];
+        for (coordinate, part) in coordinates.iter_mut().zip([left, middle, right]) {
+            let Ok(value) = part.parse() else {
+                return Err(ParseError::at_token(
+                    DAY,
+                    index,
+                    line,
+                    part,
+                    "invalid coordinate",
+                ));
+            };
+            *coordinate = value;
+        }
 
+        let [x, y, z] = coordinates;
         output.push((x, y, z));
     }
-    output
+    Ok(output)
 }
 
 fn id_pairs_sorted(boxes: Vec<JunctionBox>) -> Vec<(usize, usize)> {
@@ -59,83 +80,133 @@ fn circuit_of(box_id: usize, circuits: &[Circuit]) -> usize {
     panic!("Lost track of box with id {box_id}");
 }
 
-pub fn part_1(input: &str, max: usize) -> String {
-    let boxes = parse_junction_boxes(input);
-    let circuit_count = boxes.len();
-    let mut sorted = id_pairs_sorted(boxes);
-    sorted.truncate(max);
-    let mut circuits = vec![];
+pub struct Playground {
+    boxes: Vec<JunctionBox>,
+    pairs: Vec<(usize, usize)>,
+}
+
+pub fn try_parse(input: &str) -> Result<Playground, ParseError> {
+    let boxes = parse_junction_boxes(input)?;
+    let pairs = id_pairs_sorted(boxes.clone());
+    Ok(Playground { boxes, pairs })
+}
+
+pub struct Day8 {
+    // Number of closest pairs connected in part 1
+    pub max: usize,
+}
+
+impl Solution for Day8 {
+    type Parsed = Playground;
 
-    for index in 0..circuit_count {
-        circuits.push(vec![index])
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        try_parse(input)
     }
 
-    for pair in sorted {
-        let (left, right) = pair;
-        let merge_into = circuit_of(left, &circuits);
-        let merge_from = circuit_of(right, &circuits);
+    fn part_1(&self, playground: &Self::Parsed) -> Answer {
+        let circuit_count = playground.boxes.len();
+        let mut circuits = vec![];
 
-        if merge_into == merge_from {
-            continue;
+        for index in 0..circuit_count {
+            circuits.push(vec![index])
         }
 
-        let source = circuits[merge_from].clone();
-        let target = &mut circuits[merge_into];
+        for pair in playground.pairs.iter().take(self.max) {
+            let (left, right) = *pair;
+            let merge_into = circuit_of(left, &circuits);
+            let merge_from = circuit_of(right, &circuits);
 
-        merge(target, &source);
+            if merge_into == merge_from {
+                continue;
+            }
 
-        circuits[merge_from].clear();
-    }
+            let source = circuits[merge_from].clone();
+            let target = &mut circuits[merge_into];
 
-    circuits.retain(|circuit| !circuit.is_empty());
-    circuits.sort_by_key(|circuit| circuit.len());
-    circuits.reverse();
+            merge(target, &source);
 
-    let mut product = 1;
+            circuits[merge_from].clear();
+        }
 
-    for circuit in circuits.into_iter().take(3) {
-        let value = circuit.len();
-        product *= value;
-    }
+        circuits.retain(|circuit| !circuit.is_empty());
+        circuits.sort_by_key(|circuit| circuit.len());
+        circuits.reverse();
 
-    product.to_string()
-}
+        let mut product = 1;
 
-pub fn part_2(input: &str) -> String {
-    let boxes = parse_junction_boxes(input);
-    let circuit_count = boxes.len();
-    let sorted = id_pairs_sorted(boxes.clone());
-    let mut circuits = vec![];
-    let mut remaining: Vec<usize> = (0..circuit_count).collect();
+        for circuit in circuits.into_iter().take(3) {
+            let value = circuit.len();
+            product *= value;
+        }
 
-    for index in 0..circuit_count {
-        circuits.push(vec![index])
+        product.into()
     }
 
-    for pair in sorted {
-        let (left, right) = pair;
-        let merge_into = circuit_of(left, &circuits);
-        let merge_from = circuit_of(right, &circuits);
+    fn part_2(&self, playground: &Self::Parsed) -> Answer {
+        let boxes = &playground.boxes;
+        let circuit_count = boxes.len();
+        let mut circuits = vec![];
+        let mut remaining: Vec<usize> = (0..circuit_count).collect();
 
-        if merge_into == merge_from {
-            continue;
+        for index in 0..circuit_count {
+            circuits.push(vec![index])
         }
 
-        remaining.retain(|index| *index != merge_from);
-        let source = circuits[merge_from].clone();
-        let target = &mut circuits[merge_into];
+        for pair in &playground.pairs {
+            let (left, right) = *pair;
+            let merge_into = circuit_of(left, &circuits);
+            let merge_from = circuit_of(right, &circuits);
+
+            if merge_into == merge_from {
+                continue;
+            }
 
-        merge(target, &source);
+            remaining.retain(|index| *index != merge_from);
+            let source = circuits[merge_from].clone();
+            let target = &mut circuits[merge_into];
 
-        circuits[merge_from].clear();
+            merge(target, &source);
 
-        if remaining.len() == 1 {
-            let box1 = &boxes[left];
-            let box2 = &boxes[right];
-            let product = box1.0 * box2.0;
-            return product.to_string();
+            circuits[merge_from].clear();
+
+            if remaining.len() == 1 {
+                let box1 = &boxes[left];
+                let box2 = &boxes[right];
+                let product = box1.0 * box2.0;
+                return product.into();
+            }
         }
+
+        Answer::Unsolved
+    }
+}
+
+pub fn part_1(input: &str, max: usize) -> Result<Answer, ParseError> {
+    Ok(Day8 { max }.part_1(&try_parse(input)?))
+}
+
+pub fn part_2(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day8 { max: 0 }.part_2(&try_parse(input)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        answer::Answer,
+        d8::{part_2, try_parse},
+    };
+
+    #[test]
+    fn test_part_2_never_connected() {
+        assert_eq!(part_2("162,817,812\n").unwrap(), Answer::Unsolved);
     }
 
-    String::from("0HN0!")
+    #[test]
+    fn test_malformed_box() {
+        let error = try_parse("162,817,812\n57,618\n").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 7));
+
+        let error = try_parse("162,817,812\n57,6x8,57\n").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 4));
+    }
 }
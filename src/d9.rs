@@ -1,19 +1,35 @@
 use rayon::prelude::*;
 
+use crate::{answer::Answer, error::ParseError, solution::Solution};
+
 type Point = (u64, u64);
 type Polygon = Vec<Point>;
 
-fn parse(input: &str) -> Polygon {
+const DAY: u8 = 9;
+
+pub fn try_parse(input: &str) -> Result<Polygon, ParseError> {
     let mut output = vec![];
 
-    for line in input.lines() {
-        let (left, right) = line.split_once(',').unwrap();
-        let x: u64 = left.parse().unwrap();
-        let y: u64 = right.parse().unwrap();
+    for (index, line) in input.lines().enumerate() {
+        let Some((left, right)) = line.split_once(',') else {
+            return Err(ParseError::new(
+                DAY,
+                index,
+                line.len(),
+                line,
+                "expected x,y",
+            ));
+        };
+        let Ok(x) = left.parse::<u64>() else {
+            return Err(ParseError::at_token(DAY, index, line, left, "invalid x"));
+        };
+        let Ok(y) = right.parse::<u64>() else {
+            return Err(ParseError::at_token(DAY, index, line, right, "invalid y"));
+        };
         output.push((x, y));
     }
 
-    output
+    Ok(output)
 }
 
 fn area(a: Point, b: Point) -> u64 {
@@ -22,7 +38,7 @@ fn area(a: Point, b: Point) -> u64 {
     dx * dy
 }
 
-fn max_area(tiles: Vec<Point>) -> u64 {
+fn max_area(tiles: &[Point]) -> u64 {
     let mut max_found = 0;
     for a in 0..tiles.len() {
         for b in (a + 1)..tiles.len() {
@@ -35,7 +51,7 @@ fn max_area(tiles: Vec<Point>) -> u64 {
     max_found
 }
 
-fn max_area2(tiles: Vec<Point>) -> u64 {
+fn max_area2(tiles: &Polygon) -> u64 {
     let mut max_found = 0;
 
     for a in 0..tiles.len() {
@@ -47,7 +63,7 @@ fn max_area2(tiles: Vec<Point>) -> u64 {
                     let tile_b = tiles[b];
                     let value = area(tile_a, tile_b);
 
-                    if valid_rectangle(tile_a, tile_b, &tiles) {
+                    if valid_rectangle(tile_a, tile_b, tiles) {
                         Some(value)
                     } else {
                         None
@@ -158,13 +174,150 @@ fn valid_rectangle(a: Point, b: Point, polygon: &Polygon) -> bool {
     true
 }
 
-pub fn part_1(input: &str) -> String {
-    let tiles = parse(input);
-    max_area(tiles).to_string()
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Parsed = Polygon;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        try_parse(input)
+    }
+
+    fn part_1(&self, tiles: &Self::Parsed) -> Answer {
+        max_area(tiles).into()
+    }
+
+    fn part_2(&self, polygon: &Self::Parsed) -> Answer {
+        max_area2(polygon).into()
+    }
+}
+
+pub fn part_1(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day9.part_1(&try_parse(input)?))
 }
 
-pub fn part_2(input: &str) -> String {
-    let polygon = parse(input);
+pub fn part_2(input: &str) -> Result<Answer, ParseError> {
+    Ok(Day9.part_2(&try_parse(input)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::d9::{Point, Polygon, area, max_area2, valid_rectangle};
 
-    max_area2(polygon).to_string()
+    // Columns `(width, bottom, top)` laid out left to right, neighbours are clamped to
+    // overlap so walking the tops and then the bottoms back gives a simple polygon
+    fn column_polygon(columns: &[(u64, u64, u64)]) -> Polygon {
+        let mut clamped: Vec<(u64, u64, u64)> = vec![];
+        for &(width, bottom, top) in columns {
+            let (bottom, top) = match clamped.last() {
+                Some(&(_, previous_bottom, previous_top)) => {
+                    (bottom.min(previous_top - 1), top.max(previous_bottom + 1))
+                }
+                None => (bottom, top),
+            };
+            clamped.push((width, bottom, top));
+        }
+
+        let mut x = 1;
+        let mut tops = vec![];
+        let mut bottoms = vec![];
+        for (width, bottom, top) in clamped {
+            tops.extend([(x, top), (x + width, top)]);
+            bottoms.extend([(x, bottom), (x + width, bottom)]);
+            x += width;
+        }
+        bottoms.reverse();
+
+        let mut polygon: Polygon = tops.into_iter().chain(bottoms).collect();
+        polygon.dedup();
+        polygon
+    }
+
+    // Flood fills the outside on a grid with a one tile margin, the polygon edges block it
+    fn naive_inside(polygon: &Polygon) -> Vec<Vec<bool>> {
+        let width = polygon.iter().map(|point| point.0).max().unwrap() as usize + 2;
+        let height = polygon.iter().map(|point| point.1).max().unwrap() as usize + 2;
+        let mut edge = vec![vec![false; width]; height];
+        for (index, &(x1, y1)) in polygon.iter().enumerate() {
+            let (x2, y2) = polygon[(index + 1) % polygon.len()];
+            for y in y1.min(y2)..=y1.max(y2) {
+                for x in x1.min(x2)..=x1.max(x2) {
+                    edge[y as usize][x as usize] = true;
+                }
+            }
+        }
+
+        let mut outside = vec![vec![false; width]; height];
+        let mut stack = vec![(0, 0)];
+        while let Some((x, y)) = stack.pop() {
+            if edge[y][x] || outside[y][x] {
+                continue;
+            }
+            outside[y][x] = true;
+            if x > 0 {
+                stack.push((x - 1, y));
+            }
+            if y > 0 {
+                stack.push((x, y - 1));
+            }
+            if x + 1 < width {
+                stack.push((x + 1, y));
+            }
+            if y + 1 < height {
+                stack.push((x, y + 1));
+            }
+        }
+
+        outside
+            .into_iter()
+            .map(|row| row.into_iter().map(|outside| !outside).collect())
+            .collect()
+    }
+
+    fn naive_valid_rectangle(a: Point, b: Point, inside: &[Vec<bool>]) -> bool {
+        (a.1.min(b.1)..=a.1.max(b.1))
+            .all(|y| (a.0.min(b.0)..=a.0.max(b.0)).all(|x| inside[y as usize][x as usize]))
+    }
+
+    fn columns() -> impl Strategy<Value = Vec<(u64, u64, u64)>> {
+        prop::collection::vec(
+            (1u64..4, 1u64..6, 1u64..6)
+                .prop_map(|(width, bottom, height)| (width, bottom, bottom + height)),
+            1..6,
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn test_valid_rectangle_matches_naive(columns in columns()) {
+            let polygon = column_polygon(&columns);
+            let inside = naive_inside(&polygon);
+            for &a in &polygon {
+                for &b in &polygon {
+                    prop_assert_eq!(
+                        valid_rectangle(a, b, &polygon),
+                        naive_valid_rectangle(a, b, &inside),
+                        "a={:?} b={:?} polygon={:?}", a, b, polygon
+                    );
+                }
+            }
+        }
+
+        #[test]
+        fn test_max_area2_matches_naive(columns in columns()) {
+            let polygon = column_polygon(&columns);
+            let inside = naive_inside(&polygon);
+            let mut best = 0;
+            for &a in &polygon {
+                for &b in &polygon {
+                    if naive_valid_rectangle(a, b, &inside) {
+                        best = best.max(area(a, b));
+                    }
+                }
+            }
+            prop_assert_eq!(max_area2(&polygon), best);
+        }
+    }
 }
@@ -0,0 +1,138 @@
+use std::fmt::Display;
+
+use crate::input::InputError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    // One based line and character column of the problem
+    pub line: usize,
+    pub column: usize,
+    // The whole offending line
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    // `line` is a zero based line index and `column` a byte offset into `text`
+    pub fn new(
+        day: u8,
+        line: usize,
+        column: usize,
+        text: &str,
+        message: impl Into<String>,
+    ) -> Self {
+        let column = text
+            .get(..column)
+            .map_or(column, |head| head.chars().count());
+        Self {
+            day,
+            line: line + 1,
+            column: column + 1,
+            text: text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    // `token` has to be a slice of `text`
+    pub fn at_token(
+        day: u8,
+        line: usize,
+        text: &str,
+        token: &str,
+        message: impl Into<String>,
+    ) -> Self {
+        Self::new(day, line, offset_of(text, token), text, message)
+    }
+
+    // `token` has to be a slice of `input`, the line is worked out from its position
+    pub fn in_input(day: u8, input: &str, token: &str, message: impl Into<String>) -> Self {
+        let offset = offset_of(input, token);
+        let line_start = input[..offset].rfind('\n').map_or(0, |index| index + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |index| offset + index);
+        let line = input[..line_start].matches('\n').count();
+        let text = input[line_start..line_end].trim_end_matches('\r');
+        Self::new(day, line, offset - line_start, text, message)
+    }
+}
+
+fn offset_of(outer: &str, inner: &str) -> usize {
+    let offset = (inner.as_ptr() as usize).wrapping_sub(outer.as_ptr() as usize);
+    assert!(
+        offset <= outer.len(),
+        "token is not part of the parsed text"
+    );
+    offset
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "day {} line {} column {}: {}",
+            self.day, self.line, self.column, self.message
+        )?;
+        writeln!(f, "  {}", self.text)?;
+        write!(f, "  {}^", " ".repeat(self.column - 1))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug)]
+pub enum Error {
+    Input(InputError),
+    Parse(ParseError),
+    // A solver panicked, only caught when running several parts at once
+    Panic(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Input(error) => write!(f, "{error}"),
+            Error::Parse(error) => write!(f, "{error}"),
+            Error::Panic(message) => write!(f, "solver panicked: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<InputError> for Error {
+    fn from(value: InputError) -> Self {
+        Error::Input(value)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(value: ParseError) -> Self {
+        Error::Parse(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ParseError;
+
+    #[test]
+    fn test_token_column() {
+        let line = "162,x17,812";
+        let error = ParseError::at_token(8, 2, line, &line[4..7], "invalid number");
+        assert_eq!((error.line, error.column), (3, 5));
+        assert_eq!(
+            error.to_string(),
+            "day 8 line 3 column 5: invalid number\n  162,x17,812\n      ^"
+        );
+    }
+
+    #[test]
+    fn test_token_in_input() {
+        let input = "11-22,95-115,\n1698522-x";
+        let error = ParseError::in_input(2, input, &input[22..], "invalid number");
+        assert_eq!((error.line, error.column), (2, 9));
+        assert_eq!(error.text, "1698522-x");
+    }
+}
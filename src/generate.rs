@@ -0,0 +1,292 @@
+use std::{fmt::Write, ops::RangeInclusive};
+
+// SplitMix64, tiny and stable so a seed gives the same input on every platform and release
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94d049bb133111eb);
+        value ^ (value >> 31)
+    }
+
+    // Modulo bias is irrelevant for the ranges used here
+    pub fn range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let span = range.end() - range.start();
+        match span.checked_add(1) {
+            Some(count) => range.start() + self.next_u64() % count,
+            None => self.next_u64(),
+        }
+    }
+
+    pub fn index(&mut self, len: usize) -> usize {
+        self.range(0..=len as u64 - 1) as usize
+    }
+
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.range(1..=100) <= percent
+    }
+}
+
+pub const DAYS: [u8; 9] = [1, 2, 3, 4, 5, 6, 7, 8, 11];
+
+// `size` is the number of lines or items, grids are `size` wide. None for days without a generator
+pub fn generate(day: u8, seed: u64, size: usize) -> Option<String> {
+    let mut rng = Rng::new(seed);
+    let size = size.max(1);
+    let input = match day {
+        1 => rotations(&mut rng, size),
+        2 => id_ranges(&mut rng, size),
+        3 => banks(&mut rng, size),
+        4 => rolls(&mut rng, size),
+        5 => ingredients(&mut rng, size),
+        6 => worksheet(&mut rng, size),
+        7 => manifold(&mut rng, size),
+        8 => junction_boxes(&mut rng, size),
+        11 => devices(&mut rng, size),
+        _ => return None,
+    };
+    Some(input)
+}
+
+fn rotations(rng: &mut Rng, size: usize) -> String {
+    let mut output = String::new();
+    for _ in 0..size {
+        let direction = if rng.chance(50) { 'L' } else { 'R' };
+        writeln!(output, "{direction}{}", rng.range(1..=999)).unwrap();
+    }
+    output
+}
+
+fn id_ranges(rng: &mut Rng, size: usize) -> String {
+    let ranges: Vec<String> = (0..size)
+        .map(|_| {
+            let digits = rng.range(1..=10) as u32;
+            let start = rng.range(10u64.pow(digits - 1)..=10u64.pow(digits) - 1);
+            let width = rng.range(0..=(start / 10).min(1_000_000));
+            format!("{start}-{}", start + width)
+        })
+        .collect();
+    ranges.join(",") + "\n"
+}
+
+fn banks(rng: &mut Rng, size: usize) -> String {
+    let mut output = String::new();
+    for _ in 0..size {
+        for _ in 0..100 {
+            write!(output, "{}", rng.range(1..=9)).unwrap();
+        }
+        output.push('\n');
+    }
+    output
+}
+
+fn rolls(rng: &mut Rng, size: usize) -> String {
+    let mut output = String::new();
+    for _ in 0..size {
+        for _ in 0..size {
+            output.push(if rng.chance(65) { '@' } else { '.' });
+        }
+        output.push('\n');
+    }
+    output
+}
+
+fn ingredients(rng: &mut Rng, size: usize) -> String {
+    let mut output = String::new();
+    for _ in 0..size {
+        let start = rng.range(1..=500_000_000_000_000);
+        let width = rng.range(0..=1_000_000_000_000);
+        writeln!(output, "{start}-{}", start + width).unwrap();
+    }
+    output.push('\n');
+    for _ in 0..size {
+        writeln!(output, "{}", rng.range(1..=501_000_000_000_000)).unwrap();
+    }
+    output
+}
+
+// Numbers have at most three digits so both readings of large worksheets fit in a u64
+fn worksheet(rng: &mut Rng, size: usize) -> String {
+    const ROWS: usize = 4;
+    let mut lines = vec![String::new(); ROWS + 1];
+    for problem in 0..size {
+        if problem > 0 {
+            lines.iter_mut().for_each(|line| line.push(' '));
+        }
+        let numbers: Vec<String> = (0..ROWS).map(|_| rng.range(1..=999).to_string()).collect();
+        let width = numbers.iter().map(String::len).max().unwrap();
+        let left_aligned = rng.chance(50);
+        for (line, number) in lines.iter_mut().zip(&numbers) {
+            if left_aligned {
+                write!(line, "{number:<width$}").unwrap();
+            } else {
+                write!(line, "{number:>width$}").unwrap();
+            }
+        }
+        let operator = if rng.chance(50) { '+' } else { '*' };
+        write!(lines[ROWS], "{operator:<width$}").unwrap();
+    }
+    lines.join("\n") + "\n"
+}
+
+// Every splitter row at most doubles the part 2 timelines, so 63 of them keep it in a u64
+const MANIFOLD_DEPTH: usize = 2 * 63;
+
+// Splitters sit on every other row inside the cone the beam can reach, like the real input.
+// Wide manifolds stop at `MANIFOLD_DEPTH` rows so part 2 never overflows
+fn manifold(rng: &mut Rng, size: usize) -> String {
+    let width = size | 1;
+    let center = width / 2;
+    let mut output = String::new();
+    for y in 0..=width.min(MANIFOLD_DEPTH) {
+        let mut row = vec!['.'; width];
+        if y == 0 {
+            row[center] = 'S';
+        } else if y % 2 == 0 {
+            let spread = (y / 2 - 1).min(center);
+            for x in (center - spread..=center + spread).step_by(2) {
+                if rng.chance(75) {
+                    row[x] = '^';
+                }
+            }
+        }
+        output.extend(row);
+        output.push('\n');
+    }
+    output
+}
+
+fn junction_boxes(rng: &mut Rng, size: usize) -> String {
+    let mut output = String::new();
+    for _ in 0..size {
+        let [x, y, z] = [(); 3].map(|_| rng.range(0..=99_999));
+        writeln!(output, "{x},{y},{z}").unwrap();
+    }
+    output
+}
+
+// A shuffled DAG from `svr` over `you`, `fft` and `dac` to `out`. Nodes only link a few
+// steps ahead and never past `fft` or `dac`, so every path from `svr` visits both, and
+// few nodes branch so the number of paths stays within a u64
+// `index` written in base 26 with `a` to `z` as digits
+fn device_name(mut index: usize, letters: u32) -> String {
+    let mut name = vec![b'a'; letters as usize];
+    for letter in name.iter_mut().rev() {
+        *letter += (index % 26) as u8;
+        index /= 26;
+    }
+    String::from_utf8(name).unwrap()
+}
+
+fn devices(rng: &mut Rng, size: usize) -> String {
+    let count = size.max(6);
+    let reserved = ["svr", "you", "fft", "dac", "out"];
+    // Three letters like the puzzle, more once they run out
+    let mut letters = 3;
+    while 26usize.pow(letters) < count + reserved.len() {
+        letters += 1;
+    }
+    let space = 26usize.pow(letters);
+    let mut names = vec![String::from("svr"), String::from("you")];
+    let mut next = rng.index(space);
+    while names.len() < count - 3 {
+        let name = device_name(next % space, letters);
+        next += 1;
+        if !reserved.contains(&name.as_str()) {
+            names.push(name);
+        }
+    }
+    let fft = count / 3;
+    let dac = 2 * count / 3;
+    names.insert(fft, String::from("fft"));
+    names.insert(dac, String::from("dac"));
+    names.push(String::from("out"));
+
+    let branch_percent = (4000 / count as u64).clamp(1, 50);
+    let mut lines = vec![];
+    for from in 0..names.len() - 1 {
+        let limit = [fft, dac, names.len() - 1]
+            .into_iter()
+            .find(|stop| *stop > from)
+            .unwrap();
+        let outputs = if rng.chance(branch_percent) { 2 } else { 1 };
+        let mut targets = vec![];
+        for _ in 0..outputs {
+            let target = (from + 1 + rng.index(3)).min(limit);
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+        let targets: Vec<&str> = targets
+            .into_iter()
+            .map(|target| names[target].as_str())
+            .collect();
+        lines.push(format!("{}: {}", names[from], targets.join(" ")));
+    }
+
+    for index in (1..lines.len()).rev() {
+        lines.swap(index, rng.index(index + 1));
+    }
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        answer::Answer,
+        generate::{DAYS, generate},
+        input::InputKind,
+        registry,
+    };
+
+    #[test]
+    fn test_same_seed_same_input() {
+        for day in DAYS {
+            assert_eq!(generate(day, 7, 20), generate(day, 7, 20), "day={day}");
+            assert_ne!(generate(day, 7, 20), generate(day, 8, 20), "day={day}");
+        }
+        assert_eq!(generate(9, 7, 20), None);
+    }
+
+    #[test]
+    fn test_default_manifold_fits_u64() {
+        let input = generate(7, 0, 1000).unwrap();
+        let answer = registry::find(7, 2).unwrap().solve(&input, InputKind::Real);
+        assert!(matches!(answer, Ok(Answer::Unsigned(_))), "{answer:?}");
+    }
+
+    #[test]
+    fn test_more_devices_than_three_letters() {
+        let input = generate(11, 0, 20_000).unwrap();
+        let devices: HashSet<&str> = input
+            .lines()
+            .map(|line| line.split_once(':').unwrap().0)
+            .collect();
+        assert_eq!(devices.len(), 19_999);
+        assert!(devices.iter().any(|device| device.len() == 4));
+    }
+
+    #[test]
+    fn test_generated_inputs_solve() {
+        for day in DAYS {
+            for seed in 0..3 {
+                let input = generate(day, seed, 12).unwrap();
+                for entry in registry::day(day) {
+                    let result = entry.solve(&input, InputKind::Real);
+                    assert!(result.is_ok(), "day={day} seed={seed}: {result:?}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use crate::error::ParseError;
+
+// Column and row, (0, 0) is the top left cell
+pub type Point = (usize, usize);
+
+const OFFSETS_4: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+// Cells are stored row by row in one allocation
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut cell: impl FnMut(Point) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(&mut cell)
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    // One row per line, every line has to be as wide as the first. `cell` turns a character
+    // and its position into a cell or explains why it is not one
+    pub fn parse(
+        day: u8,
+        input: &str,
+        cell: impl FnMut(char, Point) -> Result<T, &'static str>,
+    ) -> Result<Self, ParseError> {
+        Self::parse_in(day, input, input, cell)
+    }
+
+    // Like `parse` for a map that is a slice of a larger `input`, errors point into `input`
+    pub fn parse_in(
+        day: u8,
+        input: &str,
+        map: &str,
+        mut cell: impl FnMut(char, Point) -> Result<T, &'static str>,
+    ) -> Result<Self, ParseError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = vec![];
+        for (y, line) in map.lines().enumerate() {
+            let mut x = 0;
+            for (offset, char) in line.char_indices() {
+                let token = &line[offset..offset + char.len_utf8()];
+                let value = cell(char, (x, y))
+                    .map_err(|message| ParseError::in_input(day, input, token, message))?;
+                cells.push(value);
+                x += 1;
+            }
+            let expected = *width.get_or_insert(x);
+            if x != expected {
+                let message = format!("row is {x} wide, expected {expected}");
+                return Err(ParseError::in_input(day, input, line, message));
+            }
+            height += 1;
+        }
+        Ok(Self {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Point) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.contains(point)
+            .then(|| &self.cells[point.1 * self.width + point.0])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.contains(point)
+            .then(|| &mut self.cells[point.1 * self.width + point.0])
+    }
+
+    // `point` moved by `dx` and `dy`, None when that leaves the grid
+    pub fn offset(&self, (x, y): Point, dx: isize, dy: isize) -> Option<Point> {
+        let point = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        self.contains(point).then_some(point)
+    }
+
+    // Up, left, right and down, skipping the ones outside the grid
+    pub fn neighbours_4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        OFFSETS_4
+            .iter()
+            .filter_map(move |(dx, dy)| self.offset(point, *dx, *dy))
+    }
+
+    // Also the diagonals, row by row
+    pub fn neighbours_8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        OFFSETS_8
+            .iter()
+            .filter_map(move |(dx, dy)| self.offset(point, *dx, *dy))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        (0..self.height).map(move |y| &self[(x, y)])
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Point> + use<T> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    // Every cell with its position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    pub fn map<U>(&self, cell: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(cell).collect(),
+        }
+    }
+}
+
+// Panics outside the grid, use `get` when that can happen
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): Point) -> &T {
+        assert!(x < self.width, "x={x} outside width={}", self.width);
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, (x, y): Point) -> &mut T {
+        assert!(x < self.width, "x={x} outside width={}", self.width);
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::Grid;
+
+    fn digits(input: &str) -> Grid<u32> {
+        Grid::parse(0, input, |char, _| {
+            char.to_digit(10).ok_or("expected a digit")
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let grid = digits("123\n456\n");
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 6);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.to_string(), "123\n456\n");
+        assert_eq!(Grid::<u32>::parse(0, "", |_, _| Ok(0)).unwrap().width(), 0);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let result = Grid::parse(4, "12\n1x\n", |char, _| char.to_digit(10).ok_or("digit"));
+        let error = result.unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+
+        let error = Grid::parse(4, "12\n123\n", |_, _| Ok(())).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.message, "row is 3 wide, expected 2");
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = digits("123\n456\n789\n");
+        let values = |points: Vec<(usize, usize)>| -> Vec<u32> {
+            points.into_iter().map(|point| grid[point]).collect()
+        };
+        assert_eq!(
+            values(grid.neighbours_4((1, 1)).collect()),
+            vec![2, 4, 6, 8]
+        );
+        assert_eq!(values(grid.neighbours_4((0, 0)).collect()), vec![2, 4]);
+        assert_eq!(
+            values(grid.neighbours_8((1, 1)).collect()),
+            vec![1, 2, 3, 4, 6, 7, 8, 9]
+        );
+        assert_eq!(values(grid.neighbours_8((2, 2)).collect()), vec![5, 6, 8]);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let mut grid = digits("12\n34\n56\n");
+        let rows: Vec<&[u32]> = grid.rows().collect();
+        assert_eq!(rows, vec![&[1, 2][..], &[3, 4], &[5, 6]]);
+        let columns: Vec<Vec<u32>> = grid
+            .columns()
+            .map(|column| column.copied().collect())
+            .collect();
+        assert_eq!(columns, vec![vec![1, 3, 5], vec![2, 4, 6]]);
+
+        grid.row_mut(1)[0] = 0;
+        grid[(1, 2)] = 9;
+        let doubled = grid.map(|value| value * 2);
+        assert_eq!(doubled.to_string(), "24\n08\n1018\n");
+        assert_eq!(
+            Grid::from_fn(2, 2, |(x, y)| x + 2 * y).to_string(),
+            "01\n23\n"
+        );
+    }
+}
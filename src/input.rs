@@ -1,3 +1,15 @@
+use std::{
+    fmt::Display,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use serde::Serialize;
+
+// Puzzle examples ship with the repository and are always embedded, real inputs are not
+// redistributable and are only embedded with the `embed-inputs` feature.
 pub const D1E: &str = include_str!("../input/1e.txt");
 pub const D2E: &str = include_str!("../input/2e.txt");
 pub const D3E: &str = include_str!("../input/3e.txt");
@@ -9,17 +21,290 @@ pub const D8E: &str = include_str!("../input/8e.txt");
 pub const D9E: &str = include_str!("../input/9e.txt");
 pub const D10E: &str = include_str!("../input/10e.txt");
 pub const D11E: &str = include_str!("../input/11e.txt");
+pub const D11E2: &str = include_str!("../input/11e2.txt");
 pub const D12E: &str = include_str!("../input/12e.txt");
 
-pub const D1: &str = include_str!("../input/1.txt");
-pub const D2: &str = include_str!("../input/2.txt");
-pub const D3: &str = include_str!("../input/3.txt");
-pub const D4: &str = include_str!("../input/4.txt");
-pub const D5: &str = include_str!("../input/5.txt");
-pub const D6: &str = include_str!("../input/6.txt");
-pub const D7: &str = include_str!("../input/7.txt");
-pub const D8: &str = include_str!("../input/8.txt");
-pub const D9: &str = include_str!("../input/9.txt");
-pub const D10: &str = include_str!("../input/10.txt");
-pub const D11: &str = include_str!("../input/11.txt");
-pub const D12: &str = include_str!("../input/12.txt");
+#[cfg(feature = "embed-inputs")]
+mod embedded {
+    pub const D1: &str = include_str!("../input/1.txt");
+    pub const D2: &str = include_str!("../input/2.txt");
+    pub const D3: &str = include_str!("../input/3.txt");
+    pub const D4: &str = include_str!("../input/4.txt");
+    pub const D5: &str = include_str!("../input/5.txt");
+    pub const D6: &str = include_str!("../input/6.txt");
+    pub const D7: &str = include_str!("../input/7.txt");
+    pub const D8: &str = include_str!("../input/8.txt");
+    pub const D9: &str = include_str!("../input/9.txt");
+    pub const D10: &str = include_str!("../input/10.txt");
+    pub const D11: &str = include_str!("../input/11.txt");
+    pub const D12: &str = include_str!("../input/12.txt");
+}
+
+#[cfg(feature = "embed-inputs")]
+pub use embedded::*;
+
+#[cfg(feature = "embed-inputs")]
+pub fn real(day: u8) -> Option<&'static str> {
+    let input = match day {
+        1 => D1,
+        2 => D2,
+        3 => D3,
+        4 => D4,
+        5 => D5,
+        6 => D6,
+        7 => D7,
+        8 => D8,
+        9 => D9,
+        10 => D10,
+        11 => D11,
+        12 => D12,
+        _ => return None,
+    };
+    Some(input)
+}
+
+// Day 11 part 2 has its own example, every other part shares one per day
+pub fn example(day: u8, part: u8) -> Option<&'static str> {
+    let input = match (day, part) {
+        (1, _) => D1E,
+        (2, _) => D2E,
+        (3, _) => D3E,
+        (4, _) => D4E,
+        (5, _) => D5E,
+        (6, _) => D6E,
+        (7, _) => D7E,
+        (8, _) => D8E,
+        (9, _) => D9E,
+        (10, _) => D10E,
+        (11, 2) => D11E2,
+        (11, _) => D11E,
+        (12, _) => D12E,
+        _ => return None,
+    };
+    Some(input)
+}
+
+#[cfg(not(feature = "embed-inputs"))]
+pub fn real(_day: u8) -> Option<&'static str> {
+    None
+}
+
+pub const DIR_ENV: &str = "AOC_INPUT_DIR";
+pub const DEFAULT_DIR: &str = "input";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputKind {
+    Real,
+    Example,
+}
+
+pub fn file_name(day: u8, part: u8, kind: InputKind) -> String {
+    match (kind, day, part) {
+        (InputKind::Real, _, _) => format!("{day}.txt"),
+        (InputKind::Example, 11, 2) => String::from("11e2.txt"),
+        (InputKind::Example, _, _) => format!("{day}e.txt"),
+    }
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Missing { path: PathBuf },
+    Io { path: PathBuf, error: io::Error },
+    Stdin(io::ErrorKind),
+    // Reading from a caller supplied reader failed
+    Read(io::Error),
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::Missing { path } => write!(f, "missing input file={}", path.display()),
+            InputError::Io { path, error } => {
+                write!(f, "failed to read input file={}: {error}", path.display())
+            }
+            InputError::Stdin(kind) => write!(f, "failed to read input from stdin: {kind}"),
+            InputError::Read(error) => write!(f, "failed to read input: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    // Directory holding `N.txt` and `Ne.txt` files
+    Dir(PathBuf),
+    // One file used for whatever day and part is run
+    File(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    pub fn from_env() -> Self {
+        match std::env::var_os(DIR_ENV) {
+            Some(dir) => Source::Dir(dir.into()),
+            None => Source::Dir(PathBuf::from(DEFAULT_DIR)),
+        }
+    }
+
+    // `-` reads stdin like most command line tools
+    pub fn from_arg(value: &str) -> Self {
+        match value {
+            "-" => Source::Stdin,
+            path => Source::File(PathBuf::from(path)),
+        }
+    }
+
+    pub fn path(&self, day: u8, part: u8, kind: InputKind) -> Option<PathBuf> {
+        match self {
+            Source::Dir(dir) => Some(dir.join(file_name(day, part, kind))),
+            Source::File(path) => Some(path.clone()),
+            Source::Stdin => None,
+        }
+    }
+
+    // File name the input is known by, used to look up answers
+    pub fn name(&self, day: u8, part: u8, kind: InputKind) -> String {
+        match self {
+            Source::Dir(_) => file_name(day, part, kind),
+            Source::File(path) => path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string()),
+            Source::Stdin => String::from("-"),
+        }
+    }
+
+    pub fn load(&self, day: u8, part: u8, kind: InputKind) -> Result<String, InputError> {
+        match self {
+            Source::Dir(dir) => {
+                let path = dir.join(file_name(day, part, kind));
+                match read_file(&path) {
+                    Err(InputError::Missing { path }) => {
+                        let embedded = match kind {
+                            InputKind::Real => real(day),
+                            InputKind::Example => example(day, part),
+                        };
+                        embedded
+                            .map(String::from)
+                            .ok_or(InputError::Missing { path })
+                    }
+                    result => result,
+                }
+            }
+            Source::File(path) => read_file(path),
+            Source::Stdin => read_stdin(),
+        }
+    }
+
+    // Like `load` without reading everything up front, for inputs larger than memory
+    pub fn reader(
+        &self,
+        day: u8,
+        part: u8,
+        kind: InputKind,
+    ) -> Result<Box<dyn BufRead>, InputError> {
+        let path = match self {
+            Source::Dir(dir) => dir.join(file_name(day, part, kind)),
+            Source::File(path) => path.clone(),
+            Source::Stdin => return Ok(Box::new(io::stdin().lock())),
+        };
+        match open_file(&path) {
+            Ok(file) => Ok(Box::new(BufReader::new(file))),
+            Err(InputError::Missing { .. }) if matches!(self, Source::Dir(_)) => {
+                let embedded = match kind {
+                    InputKind::Real => real(day),
+                    InputKind::Example => example(day, part),
+                };
+                embedded
+                    .map(|input| Box::new(input.as_bytes()) as Box<dyn BufRead>)
+                    .ok_or(InputError::Missing { path })
+            }
+            Err(error) => Err(error),
+        }
+    }
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Dir(dir) => write!(f, "{}", dir.display()),
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Stdin => write!(f, "<stdin>"),
+        }
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|error| file_error(path, error))
+}
+
+fn open_file(path: &Path) -> Result<File, InputError> {
+    File::open(path).map_err(|error| file_error(path, error))
+}
+
+fn file_error(path: &Path, error: io::Error) -> InputError {
+    match error.kind() {
+        io::ErrorKind::NotFound => InputError::Missing {
+            path: path.to_path_buf(),
+        },
+        _ => InputError::Io {
+            path: path.to_path_buf(),
+            error,
+        },
+    }
+}
+
+// Stdin can only be consumed once, every later load gets the same text
+fn read_stdin() -> Result<String, InputError> {
+    static STDIN: OnceLock<Result<String, io::ErrorKind>> = OnceLock::new();
+    STDIN
+        .get_or_init(|| {
+            let mut buffer = String::new();
+            io::stdin()
+                .read_to_string(&mut buffer)
+                .map(|_| buffer)
+                .map_err(|error| error.kind())
+        })
+        .clone()
+        .map_err(InputError::Stdin)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::input::{D11E2, InputError, InputKind, Source, file_name};
+
+    fn missing_dir() -> Source {
+        Source::Dir(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("no-such-dir"))
+    }
+
+    #[test]
+    fn test_file_names() {
+        assert_eq!(file_name(3, 1, InputKind::Real), "3.txt");
+        assert_eq!(file_name(3, 2, InputKind::Example), "3e.txt");
+        assert_eq!(file_name(11, 2, InputKind::Example), "11e2.txt");
+    }
+
+    #[test]
+    fn test_example_falls_back_to_embedded() {
+        let input = missing_dir().load(11, 2, InputKind::Example).unwrap();
+        assert_eq!(input, D11E2);
+    }
+
+    #[cfg(not(feature = "embed-inputs"))]
+    #[test]
+    fn test_missing_real_input() {
+        let error = missing_dir().load(1, 1, InputKind::Real).unwrap_err();
+        assert!(matches!(error, InputError::Missing { .. }));
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let source = Source::from_arg("no-such-file.txt");
+        let error = source.load(1, 1, InputKind::Real).unwrap_err();
+        assert!(matches!(error, InputError::Missing { .. }));
+    }
+}
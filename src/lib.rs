@@ -12,4 +12,14 @@ pub mod d10;
 pub mod d11;
 pub mod d12;
 
+pub mod answer;
+pub mod answers;
+pub mod bench;
+pub mod error;
+pub mod generate;
+pub mod grid;
 pub mod input;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod solution;
@@ -0,0 +1,185 @@
+use crate::{
+    answer::Answer,
+    d1, d2, d3, d4, d5, d6, d7, d8, d9, d10, d11, d12,
+    error::{Error, ParseError},
+    input::{InputError, InputKind, Source},
+    solution::{AnyParsed, DynSolution},
+};
+
+pub struct Entry {
+    pub day: u8,
+    pub part: u8,
+}
+
+impl Entry {
+    const fn new(day: u8, part: u8) -> Self {
+        Self { day, part }
+    }
+
+    pub fn solution(&self, kind: InputKind) -> &'static dyn DynSolution {
+        solution(self.day, kind).expect("registered day has a solution")
+    }
+
+    pub fn parse(&self, input: &str, kind: InputKind) -> Result<AnyParsed, ParseError> {
+        self.solution(kind).parse_any(input)
+    }
+
+    pub fn solve_parsed(&self, parsed: &AnyParsed, kind: InputKind) -> Answer {
+        self.solution(kind).solve_any(self.part, parsed)
+    }
+
+    pub fn solve(&self, input: &str, kind: InputKind) -> Result<Answer, ParseError> {
+        Ok(self.solve_parsed(&self.parse(input, kind)?, kind))
+    }
+
+    pub fn load(&self, source: &Source, kind: InputKind) -> Result<String, InputError> {
+        source.load(self.day, self.part, kind)
+    }
+
+    pub fn run(&self, source: &Source, kind: InputKind) -> Result<Answer, Error> {
+        Ok(self.solve(&self.load(source, kind)?, kind)?)
+    }
+}
+
+// Day 8 connects a different number of closest pairs in the example, which only has 20 boxes
+pub fn solution(day: u8, kind: InputKind) -> Option<&'static dyn DynSolution> {
+    let solution: &'static dyn DynSolution = match (day, kind) {
+        (1, _) => &d1::Day1,
+        (2, _) => &d2::Day2,
+        (3, _) => &d3::Day3,
+        (4, _) => &d4::Day4,
+        (5, _) => &d5::Day5,
+        (6, _) => &d6::Day6,
+        (7, _) => &d7::Day7,
+        (8, InputKind::Real) => &d8::Day8 { max: 1000 },
+        (8, InputKind::Example) => &d8::Day8 { max: 10 },
+        (9, _) => &d9::Day9,
+        (10, _) => &d10::Day10,
+        (11, _) => &d11::Day11,
+        (12, _) => &d12::Day12,
+        _ => return None,
+    };
+    Some(solution)
+}
+
+pub const ENTRIES: [Entry; 24] = [
+    Entry::new(1, 1),
+    Entry::new(1, 2),
+    Entry::new(2, 1),
+    Entry::new(2, 2),
+    Entry::new(3, 1),
+    Entry::new(3, 2),
+    Entry::new(4, 1),
+    Entry::new(4, 2),
+    Entry::new(5, 1),
+    Entry::new(5, 2),
+    Entry::new(6, 1),
+    Entry::new(6, 2),
+    Entry::new(7, 1),
+    Entry::new(7, 2),
+    Entry::new(8, 1),
+    Entry::new(8, 2),
+    Entry::new(9, 1),
+    Entry::new(9, 2),
+    Entry::new(10, 1),
+    Entry::new(10, 2),
+    Entry::new(11, 1),
+    Entry::new(11, 2),
+    Entry::new(12, 1),
+    Entry::new(12, 2),
+];
+
+pub fn find(day: u8, part: u8) -> Option<&'static Entry> {
+    ENTRIES
+        .iter()
+        .find(|entry| entry.day == day && entry.part == part)
+}
+
+pub fn day(day: u8) -> impl Iterator<Item = &'static Entry> {
+    ENTRIES.iter().filter(move |entry| entry.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        answer::Answer::{self, NotApplicable, Unsigned},
+        input::{self, InputKind},
+        registry::{ENTRIES, find},
+    };
+
+    fn example(day: u8, part: u8) -> Answer {
+        let input = input::example(day, part).unwrap();
+        find(day, part)
+            .unwrap()
+            .solve(input, InputKind::Example)
+            .unwrap()
+    }
+
+    // One test per row solving the embedded example, `EXAMPLES` lists the rows so a
+    // newly registered part without an expected answer fails `test_every_example_declared`
+    macro_rules! examples {
+        ($($(#[$meta:meta])* $name:ident: $day:literal, $part:literal => $expected:expr;)*) => {
+            const EXAMPLES: &[(u8, u8)] = &[$(($day, $part)),*];
+
+            $(
+                #[test]
+                $(#[$meta])*
+                fn $name() {
+                    assert_eq!(example($day, $part), $expected);
+                }
+            )*
+        };
+    }
+
+    examples! {
+        test_d1_part_1_example: 1, 1 => Unsigned(3);
+        test_d1_part_2_example: 1, 2 => Unsigned(6);
+        test_d2_part_1_example: 2, 1 => Unsigned(1227775554);
+        test_d2_part_2_example: 2, 2 => Unsigned(4174379265);
+        test_d3_part_1_example: 3, 1 => Unsigned(357);
+        test_d3_part_2_example: 3, 2 => Unsigned(3121910778619);
+        test_d4_part_1_example: 4, 1 => Unsigned(13);
+        test_d4_part_2_example: 4, 2 => Unsigned(43);
+        test_d5_part_1_example: 5, 1 => Unsigned(3);
+        test_d5_part_2_example: 5, 2 => Unsigned(14);
+        test_d6_part_1_example: 6, 1 => Unsigned(4277556);
+        test_d6_part_2_example: 6, 2 => Unsigned(3263827);
+        test_d7_part_1_example: 7, 1 => Unsigned(21);
+        test_d7_part_2_example: 7, 2 => Unsigned(40);
+        test_d8_part_1_example: 8, 1 => Unsigned(40);
+        test_d8_part_2_example: 8, 2 => Unsigned(25272);
+        test_d9_part_1_example: 9, 1 => Unsigned(50);
+        test_d9_part_2_example: 9, 2 => Unsigned(24);
+        test_d10_part_1_example: 10, 1 => Unsigned(7);
+        test_d10_part_2_example: 10, 2 => Unsigned(33);
+        test_d11_part_1_example: 11, 1 => Unsigned(5);
+        test_d11_part_2_example: 11, 2 => Unsigned(2);
+        test_d12_part_1_example: 12, 1 => Unsigned(2);
+        test_d12_part_2_example: 12, 2 => NotApplicable;
+    }
+
+    #[test]
+    fn test_every_part_registered_once() {
+        for day in 1..=12 {
+            for part in 1..=2 {
+                let count = ENTRIES
+                    .iter()
+                    .filter(|entry| entry.day == day && entry.part == part)
+                    .count();
+                assert_eq!(count, 1, "day={day} part={part}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_example_declared() {
+        for entry in &ENTRIES {
+            assert!(
+                EXAMPLES.contains(&(entry.day, entry.part)),
+                "day={} part={} has no expected example answer",
+                entry.day,
+                entry.part
+            );
+        }
+    }
+}
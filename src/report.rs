@@ -0,0 +1,105 @@
+use serde::Serialize;
+
+use crate::{
+    bench::Measurement,
+    input::{InputKind, Source},
+    runner::Outcome,
+};
+
+// One JSON line per solved part, fields that do not apply are null
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub kind: InputKind,
+    // Path the input was looked up at, `-` for stdin
+    pub input: String,
+    pub input_hash: Option<String>,
+    pub answer: Option<String>,
+    // Parse and solve together
+    pub duration_ns: Option<u64>,
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bench: Option<Measurement>,
+}
+
+impl Record {
+    pub fn from_outcome(outcome: &Outcome, source: &Source) -> Self {
+        let (answer, error) = match &outcome.result {
+            Ok(answer) => (Some(answer.to_string()), None),
+            Err(error) => (None, Some(error.to_string())),
+        };
+        Self {
+            day: outcome.day,
+            part: outcome.part,
+            kind: outcome.kind,
+            input: input_path(source, outcome),
+            input_hash: outcome.input_hash.clone(),
+            answer,
+            // Nothing was timed when the input failed to load
+            duration_ns: outcome
+                .input_hash
+                .is_some()
+                .then_some(outcome.duration.as_nanos() as u64),
+            error,
+            status: None,
+            bench: None,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("records always serialize")
+    }
+}
+
+fn input_path(source: &Source, outcome: &Outcome) -> String {
+    source
+        .path(outcome.day, outcome.part, outcome.kind)
+        .map_or_else(|| String::from("-"), |path| path.display().to_string())
+}
+
+// 64 bit FNV-1a, stable across platforms and releases unlike `DefaultHasher`
+pub fn input_hash(input: &str) -> String {
+    let hash = input.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, time::Duration};
+
+    use crate::{
+        answer::Answer,
+        input::{InputKind, Source},
+        report::{Record, input_hash},
+        runner::Outcome,
+    };
+
+    #[test]
+    fn test_input_hash() {
+        assert_eq!(input_hash(""), "cbf29ce484222325");
+        assert_eq!(input_hash("a"), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn test_record_json() {
+        let outcome = Outcome {
+            day: 1,
+            part: 2,
+            kind: InputKind::Example,
+            file: String::from("1e.txt"),
+            input_hash: Some(input_hash("a")),
+            result: Ok(Answer::Unsigned(6)),
+            duration: Duration::from_nanos(1500),
+        };
+        let source = Source::Dir(PathBuf::from("input"));
+        assert_eq!(
+            Record::from_outcome(&outcome, &source).to_json(),
+            r#"{"day":1,"part":2,"kind":"example","input":"input/1e.txt","input_hash":"af63dc4c8601ec8c","answer":"6","duration_ns":1500,"error":null}"#
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+use rayon::prelude::*;
+
+use crate::{
+    answer::Answer,
+    error::Error,
+    input::{InputKind, Source},
+    registry::Entry,
+    report,
+    solution::AnyParsed,
+};
+
+#[derive(Debug)]
+pub struct Outcome {
+    pub day: u8,
+    pub part: u8,
+    pub kind: InputKind,
+    // Name the input is known by in the answers manifest
+    pub file: String,
+    // None when the input could not be loaded
+    pub input_hash: Option<String>,
+    pub result: Result<Answer, Error>,
+    // Parse and solve, loading the input is not included. A parse shared by both parts of a
+    // day only counts towards the first of them
+    pub duration: Duration,
+}
+
+// Runs the parts of one day in order, parts that load the same input share a single parse
+pub fn run_day(entries: &[&Entry], source: &Source, kind: InputKind) -> Vec<Outcome> {
+    let mut parses: Vec<(String, Result<AnyParsed, Error>)> = vec![];
+    let mut outcomes = vec![];
+    for entry in entries {
+        let file = source.name(entry.day, entry.part, kind);
+        let mut duration = Duration::ZERO;
+        let mut input_hash = None;
+        let result = match entry.load(source, kind) {
+            Err(error) => Err(Error::from(error)),
+            Ok(input) => {
+                input_hash = Some(report::input_hash(&input));
+                let start = Instant::now();
+                let shared = parses.iter().position(|(parsed, _)| *parsed == input);
+                let index = shared.unwrap_or_else(|| {
+                    let parsed = catch_panic(|| entry.parse(&input, kind).map_err(Error::from));
+                    parses.push((input, parsed));
+                    parses.len() - 1
+                });
+                let result = match &parses[index].1 {
+                    Ok(parsed) => catch_panic(|| Ok(entry.solve_parsed(parsed, kind))),
+                    Err(error) => Err(repeat(error)),
+                };
+                duration = start.elapsed();
+                result
+            }
+        };
+        outcomes.push(Outcome {
+            day: entry.day,
+            part: entry.part,
+            kind,
+            file,
+            input_hash,
+            result,
+            duration,
+        });
+    }
+    outcomes
+}
+
+// A panicking day should not take the other days of a run down with it
+fn catch_panic<T>(run: impl FnOnce() -> Result<T, Error>) -> Result<T, Error> {
+    panic::catch_unwind(AssertUnwindSafe(run))
+        .unwrap_or_else(|payload| Err(Error::Panic(panic_message(payload))))
+}
+
+// Every part sharing a failed parse reports the same error
+fn repeat(error: &Error) -> Error {
+    match error {
+        Error::Parse(error) => Error::Parse(error.clone()),
+        Error::Panic(message) => Error::Panic(message.clone()),
+        Error::Input(_) => unreachable!("inputs are loaded before parsing"),
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return message.to_string();
+    }
+    if let Some(message) = payload.downcast_ref::<String>() {
+        return message.clone();
+    }
+    String::from("unknown panic")
+}
+
+// Runs the days concurrently, outcomes keep the order of `entries`
+pub fn run_all(entries: &[&Entry], source: &Source, kind: InputKind) -> Vec<Outcome> {
+    let days: Vec<&[&Entry]> = entries.chunk_by(|a, b| a.day == b.day).collect();
+    let outcomes: Vec<Vec<Outcome>> = days
+        .par_iter()
+        .map(|day| run_day(day, source, kind))
+        .collect();
+    outcomes.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::{
+        answer::Answer,
+        input::{InputKind, Source},
+        registry::{self, Entry},
+        runner::{run_all, run_day},
+    };
+
+    #[test]
+    fn test_run_all_keeps_order() {
+        let source = Source::Dir(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("input"));
+        let entries: Vec<&Entry> = registry::day(1).chain(registry::day(8)).collect();
+        let outcomes = run_all(&entries, &source, InputKind::Example);
+
+        let answers: Vec<(u8, u8, Answer)> = outcomes
+            .into_iter()
+            .map(|outcome| (outcome.day, outcome.part, outcome.result.unwrap()))
+            .collect();
+        assert_eq!(
+            answers,
+            vec![
+                (1, 1, Answer::Unsigned(3)),
+                (1, 2, Answer::Unsigned(6)),
+                (8, 1, Answer::Unsigned(40)),
+                (8, 2, Answer::Unsigned(25272)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_day_parses_each_input() {
+        // The day 11 examples are two different files, each part needs its own parse
+        let source = Source::Dir(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("input"));
+        let entries: Vec<&Entry> = registry::day(11).collect();
+        let answers: Vec<Answer> = run_day(&entries, &source, InputKind::Example)
+            .into_iter()
+            .map(|outcome| outcome.result.unwrap())
+            .collect();
+        assert_eq!(answers, vec![Answer::Unsigned(5), Answer::Unsigned(2)]);
+    }
+}
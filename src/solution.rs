@@ -0,0 +1,40 @@
+use std::any::Any;
+
+use crate::{answer::Answer, error::ParseError};
+
+pub trait Solution {
+    type Parsed;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError>;
+    fn part_1(&self, parsed: &Self::Parsed) -> Answer;
+    fn part_2(&self, parsed: &Self::Parsed) -> Answer;
+}
+
+pub type AnyParsed = Box<dyn Any + Send + Sync>;
+
+// Object safe view of a Solution so days with different parsed types can share a registry
+pub trait DynSolution: Sync {
+    fn parse_any(&self, input: &str) -> Result<AnyParsed, ParseError>;
+    fn solve_any(&self, part: u8, parsed: &AnyParsed) -> Answer;
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution + Sync,
+    S::Parsed: Send + Sync + 'static,
+{
+    fn parse_any(&self, input: &str) -> Result<AnyParsed, ParseError> {
+        Ok(Box::new(self.parse(input)?))
+    }
+
+    fn solve_any(&self, part: u8, parsed: &AnyParsed) -> Answer {
+        let parsed = parsed
+            .downcast_ref::<S::Parsed>()
+            .expect("parsed input belongs to another day");
+        match part {
+            1 => self.part_1(parsed),
+            2 => self.part_2(parsed),
+            _ => panic!("no part={part}"),
+        }
+    }
+}